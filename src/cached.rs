@@ -0,0 +1,112 @@
+use crate::SuspendUnawareInstant;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Weak,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Shortest refresh interval of a [`CachedClock`]. Anything shorter would keep
+/// the background thread spinning on a core.
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A coarse [`SuspendUnawareInstant`] clock for hot paths, refreshed by a
+/// background thread.
+///
+/// [`CachedClock::recent`] is a single atomic load, rather than a call into the
+/// operating system like [`SuspendUnawareInstant::now`].
+///
+/// # Staleness
+///
+/// The value returned by [`CachedClock::recent`] is never ahead of
+/// [`SuspendUnawareInstant::now`], and lags behind it by at most the refresh
+/// interval, plus however long the operating system takes to schedule the
+/// background thread after it wakes up (usually well under a millisecond, but
+/// unbounded on an overloaded system). Since the background thread does not run
+/// while the system is suspended, and suspend-unaware time does not pass
+/// either, the bound still holds right after a resume.
+///
+/// Cloning a `CachedClock` is cheap, and all clones share the same background
+/// thread, which exits within one interval once every clone is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::{CachedClock, SuspendUnawareInstant};
+///
+/// let clock = CachedClock::new(Duration::from_millis(10));
+/// let recent = clock.recent();
+///
+/// assert!(recent <= SuspendUnawareInstant::now());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CachedClock {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    interval: Duration,
+    created: SuspendUnawareInstant,
+    // nanoseconds between `created` and the last refresh, so that the cached
+    // instant fits in a single atomic.
+    offset: AtomicU64,
+}
+
+impl Shared {
+    fn refresh(&self) {
+        let offset = self.created.elapsed().as_nanos() as u64;
+        self.offset.store(offset, Ordering::Relaxed);
+    }
+}
+
+impl CachedClock {
+    /// Creates a clock, and spawns a background thread which refreshes it every
+    /// `interval`. Intervals shorter than one millisecond are raised to one
+    /// millisecond, so that the thread does not spin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operating system fails to spawn the thread.
+    pub fn new(interval: Duration) -> CachedClock {
+        let interval = interval.max(MIN_INTERVAL);
+        let shared = Arc::new(Shared {
+            interval,
+            created: SuspendUnawareInstant::now(),
+            offset: AtomicU64::new(0),
+        });
+
+        let weak = Arc::downgrade(&shared);
+        thread::Builder::new()
+            .name("suspend-time-cached-clock".to_string())
+            .spawn(move || refresh_until_dropped(weak, interval))
+            .expect("failed to spawn the cached clock thread");
+
+        CachedClock { shared }
+    }
+
+    /// Returns the instant of the last refresh. See the
+    /// [staleness](CachedClock#staleness) bounds.
+    pub fn recent(&self) -> SuspendUnawareInstant {
+        self.shared.created + Duration::from_nanos(self.shared.offset.load(Ordering::Relaxed))
+    }
+
+    /// Returns the interval the clock is refreshed at, after raising it to the
+    /// minimum.
+    pub fn interval(&self) -> Duration {
+        self.shared.interval
+    }
+}
+
+fn refresh_until_dropped(shared: Weak<Shared>, interval: Duration) {
+    loop {
+        match shared.upgrade() {
+            Some(shared) => shared.refresh(),
+            None => return,
+        }
+        thread::sleep(interval);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{monotonic, platform, Instant};
+use std::{convert::Infallible, marker::PhantomData};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A clock which [`Instant`]s can be read from.
+///
+/// The implementors are uninhabited marker types, one per clock, which only
+/// serve as the parameter of [`Instant`]. An instant is `Send` and `Sync`
+/// exactly when its marker is. This trait is sealed: the clocks are
+/// provided by this library's platform backends.
+pub trait ClockSource: private::Sealed + Sized {
+    /// Name of the instant type read from this clock, as printed by `Debug`.
+    const NAME: &'static str;
+
+    /// Reads the clock. See [`Instant::now`].
+    fn now() -> Instant<Self>;
+}
+
+/// The clock of [`SuspendUnawareInstant`](crate::SuspendUnawareInstant), which
+/// does not count time the system spends suspended.
+#[derive(Debug)]
+pub enum SuspendUnaware {}
+
+impl private::Sealed for SuspendUnaware {}
+
+impl ClockSource for SuspendUnaware {
+    const NAME: &'static str = "SuspendUnawareInstant";
+
+    fn now() -> Instant<SuspendUnaware> {
+        monotonic::guard(platform::now())
+    }
+}
+
+/// The clock of [`SuspendAwareInstant`](crate::SuspendAwareInstant), which
+/// keeps counting while the system is suspended.
+#[derive(Debug)]
+pub enum SuspendAware {}
+
+impl private::Sealed for SuspendAware {}
+
+impl ClockSource for SuspendAware {
+    const NAME: &'static str = "SuspendAwareInstant";
+
+    fn now() -> Instant<SuspendAware> {
+        platform::now_suspend_aware()
+    }
+}
+
+/// The clock of [`RawSuspendUnawareInstant`](crate::RawSuspendUnawareInstant),
+/// which does not count time the system spends suspended, and is not slewed by
+/// NTP.
+#[derive(Debug)]
+pub enum Raw {}
+
+impl private::Sealed for Raw {}
+
+impl ClockSource for Raw {
+    const NAME: &'static str = "RawSuspendUnawareInstant";
+
+    fn now() -> Instant<Raw> {
+        platform::now_raw()
+    }
+}
+
+/// The clock of [`ThreadCpuInstant`](crate::ThreadCpuInstant), which counts the
+/// CPU time consumed by the current thread.
+///
+/// Unlike the other markers it is neither `Send` nor `Sync`, since its instants
+/// are meaningless on any other thread.
+#[derive(Debug)]
+pub struct ThreadCpuTime(Infallible, PhantomData<*const ()>);
+
+impl private::Sealed for ThreadCpuTime {}
+
+impl ClockSource for ThreadCpuTime {
+    const NAME: &'static str = "ThreadCpuInstant";
+
+    fn now() -> Instant<ThreadCpuTime> {
+        platform::now_thread_cpu()
+    }
+}
+
+/// The clock of [`ProcessCpuInstant`](crate::ProcessCpuInstant), which counts
+/// the CPU time consumed by all the threads of the current process.
+#[derive(Debug)]
+pub enum ProcessCpuTime {}
+
+impl private::Sealed for ProcessCpuTime {}
+
+impl ClockSource for ProcessCpuTime {
+    const NAME: &'static str = "ProcessCpuInstant";
+
+    fn now() -> Instant<ProcessCpuTime> {
+        platform::now_process_cpu()
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{SuspendUnawareInstant, NANOS_PER_SECOND};
+use std::{
+    error::Error,
+    fmt,
+    ops::{Add, Sub},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// A [`SuspendUnawareInstant`] stored as a single `u64` count of nanoseconds
+/// since boot: 8 bytes instead of 16, for large tables of timestamps.
+///
+/// This covers about 584 years of (unsuspended) uptime. Converting from a
+/// `SuspendUnawareInstant` is fallible only beyond that, and lossless
+/// otherwise. Arithmetic follows the same rules as `SuspendUnawareInstant`:
+/// results which would be negative clamp to zero, and overflows return an
+/// instant set to zero.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::{CompactInstant, SuspendUnawareInstant};
+///
+/// let last_access = CompactInstant::now();
+/// assert_eq!(std::mem::size_of_val(&last_access), 8);
+///
+/// let instant = SuspendUnawareInstant::from(last_access);
+/// assert_eq!(CompactInstant::try_from(instant), Ok(last_access));
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Default, Hash)]
+pub struct CompactInstant {
+    nanos: u64,
+}
+
+impl CompactInstant {
+    /// Returns an instant corresponding to "now". See
+    /// [`SuspendUnawareInstant::now`].
+    pub fn now() -> CompactInstant {
+        // Does not fail until the system has been up for 584 years
+        CompactInstant::try_from(SuspendUnawareInstant::now()).unwrap_or_default()
+    }
+
+    /// Returns the amount of system unsuspended time elapsed since this
+    /// instant, or zero duration if this instant is in the future.
+    pub fn elapsed(&self) -> Duration {
+        CompactInstant::now() - *self
+    }
+
+    /// Returns the number of nanoseconds since boot, not counting time spent
+    /// suspended. See [`SuspendUnawareInstant::as_nanos_since_boot`].
+    pub fn as_nanos_since_boot(&self) -> u64 {
+        self.nanos
+    }
+
+    /// Creates an instant from a value returned by
+    /// [`CompactInstant::as_nanos_since_boot`].
+    pub fn from_nanos_since_boot(nanos: u64) -> CompactInstant {
+        CompactInstant { nanos }
+    }
+}
+
+/// Error returned when converting a [`SuspendUnawareInstant`] which is too far
+/// from boot to fit in a [`CompactInstant`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompactInstantOverflowError;
+
+impl fmt::Display for CompactInstantOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Instant does not fit in a CompactInstant")
+    }
+}
+
+impl Error for CompactInstantOverflowError {}
+
+impl TryFrom<SuspendUnawareInstant> for CompactInstant {
+    type Error = CompactInstantOverflowError;
+
+    fn try_from(instant: SuspendUnawareInstant) -> Result<CompactInstant, Self::Error> {
+        instant
+            .secs
+            .checked_mul(NANOS_PER_SECOND as u64)
+            .and_then(|nanos| nanos.checked_add(instant.nanos as u64))
+            .map(|nanos| CompactInstant { nanos })
+            .ok_or(CompactInstantOverflowError)
+    }
+}
+
+impl From<CompactInstant> for SuspendUnawareInstant {
+    fn from(instant: CompactInstant) -> SuspendUnawareInstant {
+        SuspendUnawareInstant::new(
+            instant.nanos / NANOS_PER_SECOND as u64,
+            (instant.nanos % NANOS_PER_SECOND as u64) as u32,
+        )
+    }
+}
+
+impl Sub<CompactInstant> for CompactInstant {
+    type Output = Duration;
+
+    fn sub(self, rhs: CompactInstant) -> Duration {
+        Duration::from_nanos(self.nanos.saturating_sub(rhs.nanos))
+    }
+}
+
+impl Sub<Duration> for CompactInstant {
+    type Output = CompactInstant;
+
+    fn sub(self, rhs: Duration) -> CompactInstant {
+        let nanos = u64::try_from(rhs.as_nanos()).unwrap_or(u64::MAX);
+        CompactInstant {
+            nanos: self.nanos.saturating_sub(nanos),
+        }
+    }
+}
+
+impl Add<Duration> for CompactInstant {
+    type Output = CompactInstant;
+
+    fn add(self, rhs: Duration) -> CompactInstant {
+        // like SuspendUnawareInstant, return 0 on overflow
+        let nanos = u64::try_from(rhs.as_nanos())
+            .ok()
+            .and_then(|nanos| self.nanos.checked_add(nanos))
+            .unwrap_or(0);
+        CompactInstant { nanos }
+    }
+}
+
+/// A [`CompactInstant`] which can be shared between threads, backed by an
+/// [`AtomicU64`]. The methods mirror those of the standard atomics, and take
+/// the same memory orderings.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::Ordering;
+/// use suspend_time::{AtomicCompactInstant, CompactInstant};
+///
+/// let last_activity = AtomicCompactInstant::new(CompactInstant::now());
+///
+/// // on every request, from any thread
+/// last_activity.fetch_max(CompactInstant::now(), Ordering::Relaxed);
+///
+/// let idle = last_activity.load(Ordering::Relaxed).elapsed();
+/// ```
+#[derive(Debug, Default)]
+pub struct AtomicCompactInstant {
+    nanos: AtomicU64,
+}
+
+impl AtomicCompactInstant {
+    /// Creates an atomic instant set to `instant`.
+    pub const fn new(instant: CompactInstant) -> AtomicCompactInstant {
+        AtomicCompactInstant {
+            nanos: AtomicU64::new(instant.nanos),
+        }
+    }
+
+    /// Loads the instant. See [`AtomicU64::load`].
+    pub fn load(&self, order: Ordering) -> CompactInstant {
+        CompactInstant {
+            nanos: self.nanos.load(order),
+        }
+    }
+
+    /// Stores `instant`. See [`AtomicU64::store`].
+    pub fn store(&self, instant: CompactInstant, order: Ordering) {
+        self.nanos.store(instant.nanos, order);
+    }
+
+    /// Stores `instant`, and returns the previous instant. See
+    /// [`AtomicU64::swap`].
+    pub fn swap(&self, instant: CompactInstant, order: Ordering) -> CompactInstant {
+        CompactInstant {
+            nanos: self.nanos.swap(instant.nanos, order),
+        }
+    }
+
+    /// Stores `instant` if it is later than the current instant, and returns
+    /// the previous instant. See [`AtomicU64::fetch_max`].
+    pub fn fetch_max(&self, instant: CompactInstant, order: Ordering) -> CompactInstant {
+        CompactInstant {
+            nanos: self.nanos.fetch_max(instant.nanos, order),
+        }
+    }
+
+    /// Stores `new` if the current instant is `current`. Returns the previous
+    /// instant, as `Ok` if it was replaced. See [`AtomicU64::compare_exchange`].
+    pub fn compare_exchange(
+        &self,
+        current: CompactInstant,
+        new: CompactInstant,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<CompactInstant, CompactInstant> {
+        self.nanos
+            .compare_exchange(current.nanos, new.nanos, success, failure)
+            .map(|nanos| CompactInstant { nanos })
+            .map_err(|nanos| CompactInstant { nanos })
+    }
+
+    /// Consumes the atomic and returns the instant.
+    pub fn into_inner(self) -> CompactInstant {
+        CompactInstant {
+            nanos: self.nanos.into_inner(),
+        }
+    }
+}
+
+impl From<CompactInstant> for AtomicCompactInstant {
+    fn from(instant: CompactInstant) -> AtomicCompactInstant {
+        AtomicCompactInstant::new(instant)
+    }
+}
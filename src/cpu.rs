@@ -0,0 +1,52 @@
+use crate::{
+    clock::{ProcessCpuTime, ThreadCpuTime},
+    Instant,
+};
+
+/// An instant of the CPU time consumed by the current thread, which only
+/// advances while the thread is running.
+///
+/// Comparing it with a [`SuspendUnawareInstant`](crate::SuspendUnawareInstant) taken at the same time
+/// tells how much of an operation was spent on the CPU rather than waiting.
+/// Instants taken on different threads cannot be compared with each other, so
+/// they cannot be sent to or shared with another thread:
+///
+/// ```compile_fail
+/// use suspend_time::ThreadCpuInstant;
+///
+/// let cpu = ThreadCpuInstant::now();
+/// std::thread::spawn(move || cpu.elapsed());
+/// ```
+///
+/// See [`Instant`] for its invariants and arithmetic.
+///
+/// |  Platform | System call                                     |
+/// |-----------|-------------------------------------------------|
+/// | Linux     | clock_gettime (CLOCK_THREAD_CPUTIME_ID)         |
+/// | Darwin    | clock_gettime (CLOCK_THREAD_CPUTIME_ID)         |
+/// | Windows   | GetThreadTimes (kernel + user time)             |
+///
+/// # Examples
+///
+/// ```
+/// use suspend_time::{SuspendUnawareInstant, ThreadCpuInstant};
+///
+/// let active = SuspendUnawareInstant::now();
+/// let cpu = ThreadCpuInstant::now();
+/// let sum: u64 = (0..1_000_000).sum();
+/// println!("{sum} took {:?} ({:?} on the CPU)", active.elapsed(), cpu.elapsed());
+/// ```
+pub type ThreadCpuInstant = Instant<ThreadCpuTime>;
+
+/// An instant of the CPU time consumed by all the threads of the current
+/// process, which can advance faster than real time when several threads
+/// run in parallel.
+///
+/// See [`Instant`] for its invariants and arithmetic.
+///
+/// |  Platform | System call                                     |
+/// |-----------|-------------------------------------------------|
+/// | Linux     | clock_gettime (CLOCK_PROCESS_CPUTIME_ID)        |
+/// | Darwin    | clock_gettime (CLOCK_PROCESS_CPUTIME_ID)        |
+/// | Windows   | GetProcessTimes (kernel + user time)            |
+pub type ProcessCpuInstant = Instant<ProcessCpuTime>;
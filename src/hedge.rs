@@ -0,0 +1,66 @@
+use crate::sleep;
+use std::{future::Future, time::Duration};
+
+/// Runs a hedged request: starts one attempt and, if it has not finished after
+/// `delay`, starts a second attempt and returns whichever succeeds first.
+///
+/// `delay` is measured with [`sleep`], so a system suspend does not count
+/// towards it and waking up never starts a spurious second attempt.
+///
+/// - If the first attempt finishes before `delay`, its result is returned and
+///   no second attempt is made, even if it failed.
+/// - Once both attempts are running, the first `Ok` is returned and the other
+///   attempt is cancelled by dropping it. If one attempt fails, the other one
+///   is awaited, and if both fail the error of the last one is returned.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut attempt = 0;
+///     let res: Result<u32, ()> = suspend_time::hedge(Duration::from_millis(10), || {
+///         attempt += 1;
+///         let this_attempt = attempt;
+///         async move {
+///             if this_attempt == 1 {
+///                 // the first attempt is stuck
+///                 suspend_time::sleep(Duration::from_secs(60)).await;
+///             }
+///             Ok(this_attempt)
+///         }
+///     })
+///     .await;
+///
+///     assert_eq!(res, Ok(2));
+/// }
+/// ```
+pub async fn hedge<F, Fut, T, E>(delay: Duration, mut make_future: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let first = make_future();
+    tokio::pin!(first);
+    tokio::select! {
+        output = &mut first => {
+            return output;
+        }
+        _ = sleep(delay) => {}
+    }
+
+    let second = make_future();
+    tokio::pin!(second);
+    tokio::select! {
+        output = &mut first => match output {
+            Ok(output) => Ok(output),
+            Err(_) => second.await,
+        },
+        output = &mut second => match output {
+            Ok(output) => Ok(output),
+            Err(_) => first.await,
+        },
+    }
+}
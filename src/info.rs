@@ -0,0 +1,62 @@
+use crate::platform;
+use std::{io, sync::OnceLock, time::Duration};
+
+/// Describes the clock backing [`SuspendUnawareInstant`](crate::SuspendUnawareInstant)
+/// in this build, as returned by [`clock_info`].
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct ClockInfo {
+    /// The platform backend compiled in: `"linux"`, `"apple"` or `"windows"`.
+    pub backend: &'static str,
+    /// The clock id or API that is read, e.g. `"CLOCK_MONOTONIC"`.
+    pub clock: &'static str,
+    /// The resolution of the clock, as reported by the operating system.
+    pub resolution: Duration,
+    /// True if the clock does not count time the system spends suspended on
+    /// this operating system.
+    pub suspend_excluding: bool,
+    /// True if the clock's rate is slewed by NTP adjustments, rather than
+    /// following the raw hardware counter.
+    pub ntp_slewed: bool,
+}
+
+/// Returns which clock this build of the library reads, and its semantics.
+///
+/// # Examples
+///
+/// ```
+/// let info = suspend_time::clock_info();
+/// println!("suspend-time is using {} ({})", info.clock, info.backend);
+/// ```
+pub fn clock_info() -> ClockInfo {
+    platform::clock_info()
+}
+
+/// Returns an identifier of the current boot of the system, which changes
+/// every time the system boots.
+///
+/// Instants are only meaningful within the boot they were taken in, so this
+/// can tell whether an instant persisted by a previous process is still
+/// comparable with [`SuspendUnawareInstant::now`](crate::SuspendUnawareInstant::now).
+///
+/// |  Platform | Source                                          |
+/// |-----------|-------------------------------------------------|
+/// | Linux     | /proc/sys/kernel/random/boot_id                 |
+/// | Darwin    | sysctl kern.bootsessionuuid                     |
+/// | Windows   | the BootId counter in the registry              |
+///
+/// # Examples
+///
+/// ```
+/// let boot_id = suspend_time::boot_id().unwrap();
+/// assert_eq!(suspend_time::boot_id().unwrap(), boot_id);
+/// ```
+pub fn boot_id() -> io::Result<String> {
+    // The id cannot change while the process is running
+    static BOOT_ID: OnceLock<String> = OnceLock::new();
+
+    if let Some(boot_id) = BOOT_ID.get() {
+        return Ok(boot_id.clone());
+    }
+    let boot_id = platform::boot_id()?;
+    Ok(BOOT_ID.get_or_init(|| boot_id).clone())
+}
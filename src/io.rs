@@ -0,0 +1,243 @@
+use crate::{sleep_until, Sleep, SuspendUnawareInstant, TimedOutError};
+use pin_project_lite::pin_project;
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A single timeout of a [`TimeoutStream`]. The timeout is armed from
+/// `started`, and is disarmed while `started` is `None`.
+#[derive(Debug)]
+struct TimeoutState {
+    timeout: Option<Duration>,
+    started: Option<SuspendUnawareInstant>,
+    sleep: Sleep,
+}
+
+impl TimeoutState {
+    fn new(started: Option<SuspendUnawareInstant>) -> TimeoutState {
+        TimeoutState {
+            timeout: None,
+            started,
+            sleep: sleep_until(SuspendUnawareInstant::now()),
+        }
+    }
+
+    /// Arms the timeout from "now", unless it is already armed.
+    fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(SuspendUnawareInstant::now());
+        }
+    }
+
+    /// Returns an error if the timeout is armed and has expired, and otherwise
+    /// registers the current task to be woken up when it expires.
+    fn poll_check(&mut self, cx: &mut Context<'_>) -> io::Result<()> {
+        let (Some(timeout), Some(started)) = (self.timeout, self.started) else {
+            return Ok(());
+        };
+
+        // a timeout the clock cannot represent never fires
+        let deadline = started.saturating_add(timeout);
+        if self.sleep.deadline() != deadline {
+            Pin::new(&mut self.sleep).reset(deadline);
+        }
+        match Pin::new(&mut self.sleep).poll(cx) {
+            Poll::Ready(()) => Err(io::Error::new(io::ErrorKind::TimedOut, TimedOutError)),
+            Poll::Pending => Ok(()),
+        }
+    }
+}
+
+pin_project! {
+    /// Wraps a tokio [`AsyncRead`]/[`AsyncWrite`] stream with read, write and
+    /// idle timeouts, all measured with [`SuspendUnawareInstant`].
+    ///
+    /// - The read timeout bounds how long a single read can be pending without
+    ///   making progress.
+    /// - The write timeout does the same for writes, flushes and shutdowns.
+    /// - The idle timeout bounds how long the stream can go without making
+    ///   progress in either direction. It is only checked while a read or a
+    ///   write is pending.
+    ///
+    /// Every timeout is reset when the stream makes progress. An expired
+    /// timeout fails the pending operation with an [`io::Error`] of kind
+    /// [`io::ErrorKind::TimedOut`], wrapping a [`TimedOutError`]. Since time
+    /// does not pass while the system is suspended, long-lived connections are
+    /// not torn down on resume just because the machine was asleep.
+    ///
+    /// All timeouts are disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use suspend_time::TimeoutStream;
+    /// use tokio::io::AsyncReadExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let (client, _server) = tokio::io::duplex(64);
+    ///     let mut client = TimeoutStream::new(client);
+    ///     client.set_read_timeout(Some(Duration::from_millis(10)));
+    ///
+    ///     // the server never writes anything
+    ///     let err = client.read(&mut [0; 8]).await.unwrap_err();
+    ///     assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct TimeoutStream<S> {
+        #[pin]
+        stream: S,
+        read: TimeoutState,
+        write: TimeoutState,
+        idle: TimeoutState,
+    }
+}
+
+impl<S> TimeoutStream<S> {
+    /// Wraps `stream`, with every timeout disabled.
+    pub fn new(stream: S) -> TimeoutStream<S> {
+        TimeoutStream {
+            stream,
+            read: TimeoutState::new(None),
+            write: TimeoutState::new(None),
+            idle: TimeoutState::new(Some(SuspendUnawareInstant::now())),
+        }
+    }
+
+    /// Returns the read timeout.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read.timeout
+    }
+
+    /// Sets the read timeout. `None` disables it.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read.timeout = timeout;
+        self.read.started = None;
+    }
+
+    /// Returns the write timeout.
+    pub fn write_timeout(&self) -> Option<Duration> {
+        self.write.timeout
+    }
+
+    /// Sets the write timeout. `None` disables it.
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+        self.write.timeout = timeout;
+        self.write.started = None;
+    }
+
+    /// Returns the idle timeout.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle.timeout
+    }
+
+    /// Sets the idle timeout, counting from "now". `None` disables it.
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.idle.timeout = timeout;
+        self.idle.started = Some(SuspendUnawareInstant::now());
+    }
+
+    /// Returns a shared reference to the wrapped stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the wrapped stream.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// Returns a pinned mutable reference to the wrapped stream.
+    pub fn get_pin_mut(self: Pin<&mut Self>) -> Pin<&mut S> {
+        self.project().stream
+    }
+
+    /// Consumes the wrapper, returning the wrapped stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+/// Updates the timeouts of an operation after polling it.
+fn on_poll<T>(
+    cx: &mut Context<'_>,
+    poll: Poll<io::Result<T>>,
+    op: &mut TimeoutState,
+    idle: &mut TimeoutState,
+) -> Poll<io::Result<T>> {
+    match poll {
+        Poll::Pending => {
+            op.start();
+            if let Err(e) = op.poll_check(cx).and_then(|()| idle.poll_check(cx)) {
+                // Start over on the next operation, rather than failing it
+                // right away with the same expired timeouts.
+                op.started = None;
+                idle.started = Some(SuspendUnawareInstant::now());
+                return Poll::Ready(Err(e));
+            }
+            Poll::Pending
+        }
+        Poll::Ready(res) => {
+            op.started = None;
+            idle.started = Some(SuspendUnawareInstant::now());
+            Poll::Ready(res)
+        }
+    }
+}
+
+impl<S: AsyncRead> AsyncRead for TimeoutStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let poll = this.stream.poll_read(cx, buf);
+        on_poll(cx, poll, this.read, this.idle)
+    }
+}
+
+impl<S: AsyncWrite> AsyncWrite for TimeoutStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.stream.poll_write(cx, buf);
+        on_poll(cx, poll, this.write, this.idle)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.project();
+        let poll = this.stream.poll_write_vectored(cx, bufs);
+        on_poll(cx, poll, this.write, this.idle)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let poll = this.stream.poll_flush(cx);
+        on_poll(cx, poll, this.write, this.idle)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.project();
+        let poll = this.stream.poll_shutdown(cx);
+        on_poll(cx, poll, this.write, this.idle)
+    }
+}
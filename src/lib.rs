@@ -35,35 +35,79 @@
 //! ```
 //!
 use std::{
+    cmp,
     error::Error,
     fmt,
     future::Future,
+    marker::PhantomData,
     ops::{Add, Sub},
+    pin::Pin,
+    task::{ready, Context, Poll},
     time::Duration,
 };
 
+mod cached;
+mod clock;
+mod compact;
+mod cpu;
+mod hedge;
+mod info;
+mod io;
+mod monotonic;
 mod platform;
+mod policy;
+mod resume;
+mod scheduler;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod signed;
+mod snapshot;
+mod stopwatch;
+mod task;
 #[cfg(test)]
 mod tests;
+mod watchdog;
+
+pub use cached::CachedClock;
+pub use clock::{ClockSource, ProcessCpuTime, Raw, SuspendAware, SuspendUnaware, ThreadCpuTime};
+pub use compact::{AtomicCompactInstant, CompactInstant, CompactInstantOverflowError};
+pub use cpu::{ProcessCpuInstant, ThreadCpuInstant};
+pub use hedge::hedge;
+pub use info::{boot_id, clock_info, ClockInfo};
+pub use io::TimeoutStream;
+pub use monotonic::{
+    clock_anomalies, enable_monotonicity_guard, is_monotonicity_guard_enabled,
+    set_forward_jump_threshold, ClockAnomalies,
+};
+pub use policy::{sleep_until_wall, sleep_with, timeout_with, TimeoutPolicy};
+pub use resume::{cancel_on_resume, timeout_with_resume_grace, SuspendedError};
+pub use scheduler::{
+    CronParseError, CronSchedule, JobHandle, MissedRunPolicy, NextRun, Schedule, Scheduler,
+};
+pub use signed::SignedDuration;
+pub use snapshot::ClockSnapshot;
+pub use stopwatch::{Lap, Stopwatch};
+pub use task::{spawn_with_timeout, DeadlineJoinSet, TimeoutJoinHandle};
+pub use watchdog::Watchdog;
 
 const NANOS_PER_SECOND: u32 = 1_000_000_000;
+/// Stands in for "never" where std and tokio instants cannot represent a
+/// deadline. This is what tokio uses too.
+const FAR_FUTURE: Duration = Duration::from_secs(86400 * 365 * 30);
 
-/// Similar to the standard library's implementation of
-/// [`Instant`](https://doc.rust-lang.org/1.78.0/std/time/struct.Instant.html),
-/// except it is consistently unaware of system suspends across all platforms
-/// supported by this library.
+/// An instant of the clock `C`, which is one of the [`ClockSource`] markers.
 ///
-/// Historically, this has been inconsistent in the standard library, with
-/// windows allowing time to pass when the system is suspended/hibernating,
-/// however unix systems do not "pass time" during system suspension. In this
-/// library, time **never passes** when the system is suspended on **any
-/// platform**.
+/// Every clock shares this representation and its arithmetic, but instants of
+/// different clocks are different types, so they cannot be compared or
+/// subtracted by mistake. The clocks are usually named through their aliases,
+/// such as [`SuspendUnawareInstant`] and [`SuspendAwareInstant`].
 ///
-/// This instant implementation is:
-/// - Opaque (you cannot manually create an Instant. You must call ::now())
-/// - Cross platform (windows, macOS)
-/// - Monotonic (time never goes backwards)
-/// - Suspend-unaware (when you put your computer to sleep, "time" does not pass.)
+/// ```compile_fail
+/// use suspend_time::{SuspendAwareInstant, SuspendUnawareInstant};
+///
+/// // error: the clocks do not match
+/// let suspended = SuspendAwareInstant::now() - SuspendUnawareInstant::now();
+/// ```
 ///
 /// # Undefined behavior / Invariants
 /// 1. When polling the system clock, nanoseconds should never exceed 10^9 (the number of nanoseconds in 1 second).
@@ -72,35 +116,84 @@ const NANOS_PER_SECOND: u32 = 1_000_000_000;
 /// 3. If a duration is subtracted that would cause an instant to be negative, we return an instant set at 0.
 /// 4. If a duration is added to an instant that would cause the instant to exceed 2^64 seconds, we return an instant set to 0.
 ///
-/// # Underlying System calls
-///
-/// The following system calls are currently being used by `now()` to find out
-/// the current time:
-///
-/// |  Platform |               System call                               |
-/// |-----------|---------------------------------------------------------|
-/// | UNIX      | [clock_gettime] (CLOCK_UPTIME_RAW)                      |
-/// | Darwin    | [clock_gettime] (CLOCK_UPTIME_RAW)                      |
-/// | VXWorks   | [clock_gettime] (CLOCK_UPTIME_RAW)                      |
-/// | Windows   | [QueryUnbiasedInterruptTimePrecise]                     |
-///
-/// [clock_gettime]: https://www.manpagez.com/man/3/clock_gettime/
-/// [QueryUnbiasedInterruptTimePrecise]:
-/// https://learn.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryunbiasedinterrupttimeprecise
-///
 /// Certain overflows are dependent on how the standard library implements
 /// Duration.  For example, right now it is implemented as a u64 counting
 /// seconds. As such, to prevent overflow we must check if the number of seconds
 /// in two Durations exceeds the bounds of a u64.  To avoid being dependent on
 /// the standard library for cases like this, we choose our own representation
 /// of time which matches the "apple" libc platform implementation.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
-pub struct SuspendUnawareInstant {
+pub struct Instant<C> {
     secs: u64,
     nanos: u32, // invariant: 0 <= self.nanos <= NANOS_PER_SECOND
+    // the instant is Send and Sync exactly when the marker is, which lets
+    // clocks that are local to a thread opt out
+    clock: PhantomData<C>,
 }
 
-impl SuspendUnawareInstant {
+// The following traits are implemented by hand, since deriving them would
+// require the markers to implement them too.
+
+impl<C> Clone for Instant<C> {
+    fn clone(&self) -> Instant<C> {
+        *self
+    }
+}
+
+impl<C> Copy for Instant<C> {}
+
+impl<C> PartialEq for Instant<C> {
+    fn eq(&self, other: &Instant<C>) -> bool {
+        (self.secs, self.nanos) == (other.secs, other.nanos)
+    }
+}
+
+impl<C> Eq for Instant<C> {}
+
+impl<C> PartialOrd for Instant<C> {
+    fn partial_cmp(&self, other: &Instant<C>) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C> Ord for Instant<C> {
+    fn cmp(&self, other: &Instant<C>) -> cmp::Ordering {
+        (self.secs, self.nanos).cmp(&(other.secs, other.nanos))
+    }
+}
+
+impl<C: ClockSource> fmt::Debug for Instant<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(C::NAME)
+            .field("secs", &self.secs)
+            .field("nanos", &self.nanos)
+            .finish()
+    }
+}
+
+impl<C> Instant<C> {
+    pub(crate) const fn new(secs: u64, nanos: u32) -> Instant<C> {
+        Instant {
+            secs,
+            nanos,
+            clock: PhantomData,
+        }
+    }
+
+    /// Adds `duration`, saturating to the latest instant rather than wrapping
+    /// around to 0 like `+` does. Every deadline built from a caller's duration
+    /// goes through this, so that durations the clock cannot represent mean
+    /// "never" rather than "already expired".
+    pub(crate) fn saturating_add(self, duration: Duration) -> Instant<C> {
+        let sum = self + duration;
+        if sum < self {
+            Instant::new(u64::MAX, NANOS_PER_SECOND - 1)
+        } else {
+            sum
+        }
+    }
+}
+
+impl<C: ClockSource> Instant<C> {
     /// Returns an instant corresponding to "now".
     ///
     /// # Examples
@@ -110,13 +203,13 @@ impl SuspendUnawareInstant {
     ///
     /// let now = SuspendUnawareInstant::now();
     /// ```
-    pub fn now() -> SuspendUnawareInstant {
-        platform::now()
+    pub fn now() -> Instant<C> {
+        C::now()
     }
 
-    /// Returns the amount of system unsuspended time elapsed since this suspend
-    /// unaware instant was created, or zero duration if that this instant is in
-    /// the future.
+    /// Returns the amount of time elapsed on the clock since this instant was
+    /// created, or zero duration if that this instant is in the future. For a
+    /// [`SuspendUnawareInstant`], this is the system unsuspended time.
     ///
     /// # Examples
     ///
@@ -132,12 +225,245 @@ impl SuspendUnawareInstant {
     pub fn elapsed(&self) -> Duration {
         Self::now() - *self
     }
+
+    /// Returns the amount of time on the clock from `earlier` to this instant,
+    /// which is negative if `earlier` is actually later. Unlike subtracting the
+    /// instants, this does not clamp to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use suspend_time::SuspendUnawareInstant;
+    ///
+    /// let now = SuspendUnawareInstant::now();
+    /// let later = now + Duration::from_secs(1);
+    ///
+    /// assert_eq!(now - later, Duration::ZERO);
+    /// assert_eq!(now.signed_duration_since(later).as_nanos(), -1_000_000_000);
+    /// ```
+    pub fn signed_duration_since(&self, earlier: Instant<C>) -> SignedDuration {
+        if *self >= earlier {
+            SignedDuration::positive(*self - earlier)
+        } else {
+            SignedDuration::negative(earlier - *self)
+        }
+    }
 }
 
-impl Sub<SuspendUnawareInstant> for SuspendUnawareInstant {
+/// Similar to the standard library's implementation of
+/// [`Instant`](https://doc.rust-lang.org/1.78.0/std/time/struct.Instant.html),
+/// except it is consistently unaware of system suspends across all platforms
+/// supported by this library.
+///
+/// Historically, this has been inconsistent in the standard library, with
+/// windows allowing time to pass when the system is suspended/hibernating,
+/// however unix systems do not "pass time" during system suspension. In this
+/// library, time **never passes** when the system is suspended on **any
+/// platform**.
+///
+/// This instant implementation is:
+/// - Opaque (its representation is private. Instants come from ::now(), from
+///   a duration since boot with
+///   [`from_duration_since_boot`](Instant::from_duration_since_boot) or
+///   [`from_nanos_since_boot`](Instant::from_nanos_since_boot), or from
+///   another clock with [`from_std_instant`](Instant::from_std_instant) or
+///   [`from_tokio_instant`](Instant::from_tokio_instant))
+/// - Cross platform (windows, macOS, linux)
+/// - Monotonic (time never goes backwards). This relies on the operating
+///   system, unless [`enable_monotonicity_guard`] is called.
+/// - Suspend-unaware (when you put your computer to sleep, "time" does not pass.)
+///
+/// See [`Instant`] for its invariants.
+///
+/// # Underlying System calls
+///
+/// The following system calls are currently being used by `now()` to find out
+/// the current time:
+///
+/// |  Platform |               System call                               |
+/// |-----------|---------------------------------------------------------|
+/// | Linux     | [clock_gettime] (CLOCK_MONOTONIC)                       |
+/// | Darwin    | [clock_gettime] (CLOCK_UPTIME_RAW)                      |
+/// | Windows   | [QueryUnbiasedInterruptTimePrecise]                     |
+///
+/// The backend compiled into a given build can be inspected at runtime with
+/// [`clock_info`].
+///
+/// [clock_gettime]: https://www.manpagez.com/man/3/clock_gettime/
+/// [QueryUnbiasedInterruptTimePrecise]:
+/// https://learn.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryunbiasedinterrupttimeprecise
+pub type SuspendUnawareInstant = Instant<SuspendUnaware>;
+
+impl SuspendUnawareInstant {
+    /// Returns an instant corresponding to "now", read from a cheaper but
+    /// coarser clock than [`SuspendUnawareInstant::now`]. It has the same
+    /// suspend-unaware semantics, and can be compared with instants returned by
+    /// `now()` (though it is not covered by [`enable_monotonicity_guard`]),
+    /// but it only advances once per tick (see
+    /// [`SuspendUnawareInstant::coarse_resolution`]), so it lags behind
+    /// `now()` by about a tick (more on kernels that skip ticks when idle).
+    ///
+    /// | Platform  | System call                                     |
+    /// |-----------|-------------------------------------------------|
+    /// | Linux     | clock_gettime (CLOCK_MONOTONIC_COARSE)          |
+    /// | Darwin    | clock_gettime (CLOCK_UPTIME_RAW_APPROX)         |
+    /// | Windows   | QueryUnbiasedInterruptTime                      |
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use suspend_time::SuspendUnawareInstant;
+    ///
+    /// let now = SuspendUnawareInstant::now_coarse();
+    /// ```
+    pub fn now_coarse() -> SuspendUnawareInstant {
+        platform::now_coarse()
+    }
+
+    /// Returns the resolution of the clock read by
+    /// [`SuspendUnawareInstant::now`].
+    pub fn resolution() -> Duration {
+        platform::resolution()
+    }
+
+    /// Returns the resolution of the clock read by
+    /// [`SuspendUnawareInstant::now_coarse`], which is the system's timer tick
+    /// (usually between 1ms and 16ms).
+    pub fn coarse_resolution() -> Duration {
+        platform::coarse_resolution()
+    }
+
+    /// Returns the time elapsed between the system booting and this instant,
+    /// not counting time spent suspended, in nanoseconds.
+    ///
+    /// The clock is shared by every process on the system, so the value can be
+    /// sent to another process (over a pipe or shared memory, for example) and
+    /// turned back into an instant with
+    /// [`SuspendUnawareInstant::from_nanos_since_boot`]. It is meaningless
+    /// after a reboot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use suspend_time::SuspendUnawareInstant;
+    ///
+    /// let deadline = SuspendUnawareInstant::now() + Duration::from_secs(5);
+    /// let nanos = deadline.as_nanos_since_boot();
+    ///
+    /// // in another process
+    /// let deadline = SuspendUnawareInstant::from_nanos_since_boot(nanos).unwrap();
+    /// ```
+    pub fn as_nanos_since_boot(&self) -> u128 {
+        self.as_duration_since_boot().as_nanos()
+    }
+
+    /// The same as [`SuspendUnawareInstant::as_nanos_since_boot`], as a
+    /// [`Duration`].
+    pub fn as_duration_since_boot(&self) -> Duration {
+        Duration::new(self.secs, self.nanos)
+    }
+
+    /// Creates an instant from a value returned by
+    /// [`SuspendUnawareInstant::as_duration_since_boot`], possibly in another
+    /// process. Every `Duration` is a valid instant.
+    pub fn from_duration_since_boot(duration: Duration) -> SuspendUnawareInstant {
+        SuspendUnawareInstant::new(duration.as_secs(), duration.subsec_nanos())
+    }
+
+    /// Creates an instant from a value returned by
+    /// [`SuspendUnawareInstant::as_nanos_since_boot`], possibly in another
+    /// process. Returns `None` if `nanos` is too large to be an instant, which
+    /// never happens for values returned by `as_nanos_since_boot`.
+    pub fn from_nanos_since_boot(nanos: u128) -> Option<SuspendUnawareInstant> {
+        let secs = u64::try_from(nanos / NANOS_PER_SECOND as u128).ok()?;
+        Some(SuspendUnawareInstant::new(
+            secs,
+            (nanos % NANOS_PER_SECOND as u128) as u32,
+        ))
+    }
+
+    /// Converts this instant into a [`std::time::Instant`], for APIs which
+    /// only accept those.
+    ///
+    /// The conversion reads both clocks now, and offsets the result by the
+    /// suspend-unaware time between this instant and now. It is exact when the
+    /// system is not suspended in between (and always on platforms where
+    /// `Instant` is suspend-unaware too, like Linux and macOS). Otherwise, the
+    /// result is off by the time spent suspended: a deadline converted before
+    /// a suspend fires early after the resume.
+    ///
+    /// Instants too far in the past to be represented by an `Instant` are
+    /// clamped to now, and instants too far in the future are clamped to about
+    /// 30 years from now (like tokio does for its sleeps), which is as good as
+    /// never for a deadline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use suspend_time::SuspendUnawareInstant;
+    ///
+    /// let deadline = SuspendUnawareInstant::now() + Duration::from_secs(5);
+    /// let deadline = deadline.to_std_instant();
+    /// ```
+    pub fn to_std_instant(&self) -> std::time::Instant {
+        let (anchor, std_anchor) = (SuspendUnawareInstant::now(), std::time::Instant::now());
+        if *self >= anchor {
+            std_anchor
+                .checked_add(*self - anchor)
+                .unwrap_or_else(|| std_anchor + FAR_FUTURE)
+        } else {
+            std_anchor.checked_sub(anchor - *self).unwrap_or(std_anchor)
+        }
+    }
+
+    /// Converts a [`std::time::Instant`] into a suspend-unaware instant. The
+    /// same caveats as [`SuspendUnawareInstant::to_std_instant`] apply.
+    pub fn from_std_instant(instant: std::time::Instant) -> SuspendUnawareInstant {
+        let (anchor, std_anchor) = (SuspendUnawareInstant::now(), std::time::Instant::now());
+        if instant >= std_anchor {
+            anchor.saturating_add(instant - std_anchor)
+        } else {
+            anchor - (std_anchor - instant)
+        }
+    }
+
+    /// Converts this instant into a [`tokio::time::Instant`]. The same caveats
+    /// as [`SuspendUnawareInstant::to_std_instant`] apply.
+    ///
+    /// This follows tokio's clock, so it stays correct when time is paused in
+    /// tests.
+    pub fn to_tokio_instant(&self) -> tokio::time::Instant {
+        let (anchor, tokio_anchor) = (SuspendUnawareInstant::now(), tokio::time::Instant::now());
+        if *self >= anchor {
+            tokio_anchor
+                .checked_add(*self - anchor)
+                .unwrap_or_else(|| tokio_anchor + FAR_FUTURE)
+        } else {
+            tokio_anchor
+                .checked_sub(anchor - *self)
+                .unwrap_or(tokio_anchor)
+        }
+    }
+
+    /// Converts a [`tokio::time::Instant`] into a suspend-unaware instant. The
+    /// same caveats as [`SuspendUnawareInstant::to_std_instant`] apply.
+    pub fn from_tokio_instant(instant: tokio::time::Instant) -> SuspendUnawareInstant {
+        let (anchor, tokio_anchor) = (SuspendUnawareInstant::now(), tokio::time::Instant::now());
+        if instant >= tokio_anchor {
+            anchor.saturating_add(instant - tokio_anchor)
+        } else {
+            anchor - (tokio_anchor - instant)
+        }
+    }
+}
+
+impl<C> Sub<Instant<C>> for Instant<C> {
     type Output = Duration;
 
-    fn sub(self, rhs: SuspendUnawareInstant) -> Duration {
+    fn sub(self, rhs: Instant<C>) -> Duration {
         if rhs > self {
             Duration::new(0, 0)
         } else {
@@ -152,51 +478,48 @@ impl Sub<SuspendUnawareInstant> for SuspendUnawareInstant {
     }
 }
 
-// When adding/subtracting a `Duration` to/from a SuspendUnawareInstant, we want
+// When adding/subtracting a `Duration` to/from an Instant, we want
 // the result to be a new instant (point in time)
 
-impl Sub<Duration> for SuspendUnawareInstant {
-    type Output = SuspendUnawareInstant;
+impl<C> Sub<Duration> for Instant<C> {
+    type Output = Instant<C>;
 
-    fn sub(self, rhs: Duration) -> SuspendUnawareInstant {
+    fn sub(self, rhs: Duration) -> Instant<C> {
         let rhs_secs = rhs.as_secs();
         let rhs_nanos = rhs.subsec_nanos();
 
         if self.secs.checked_sub(rhs_secs).is_none() {
-            SuspendUnawareInstant { secs: 0, nanos: 0 }
+            Instant::new(0, 0)
         } else if rhs_nanos > self.nanos {
             // Since (self.secs - rhs_secs) passed, we know that self.secs >= rhs_secs.
             // The only case in which rhs_nanos > self.nanos is a problem is
             // when self.secs == rhs_secs, since this will cause the instant
             // to be "negative".
             if self.secs == rhs_secs {
-                SuspendUnawareInstant { secs: 0, nanos: 0 }
+                Instant::new(0, 0)
             } else {
-                SuspendUnawareInstant {
-                    secs: self.secs - rhs_secs - 1,
-                    nanos: (NANOS_PER_SECOND + self.nanos) - rhs_nanos,
-                }
+                Instant::new(
+                    self.secs - rhs_secs - 1,
+                    (NANOS_PER_SECOND + self.nanos) - rhs_nanos,
+                )
             }
         } else {
-            SuspendUnawareInstant {
-                secs: self.secs - rhs_secs,
-                nanos: self.nanos - rhs_nanos,
-            }
+            Instant::new(self.secs - rhs_secs, self.nanos - rhs_nanos)
         }
     }
 }
 
-impl Add<Duration> for SuspendUnawareInstant {
-    type Output = SuspendUnawareInstant;
+impl<C> Add<Duration> for Instant<C> {
+    type Output = Instant<C>;
 
     #[allow(clippy::suspicious_arithmetic_impl)]
-    fn add(self, rhs: Duration) -> SuspendUnawareInstant {
+    fn add(self, rhs: Duration) -> Instant<C> {
         let rhs_secs = rhs.as_secs();
         let rhs_nanos = rhs.subsec_nanos();
 
         if self.secs.checked_add(rhs_secs).is_none() {
             // undefined behavior, return 0
-            SuspendUnawareInstant { secs: 0, nanos: 0 }
+            Instant::new(0, 0)
         } else {
             let nanos_carry = (self.nanos + rhs_nanos) / NANOS_PER_SECOND;
             // very pedantic edge case where the nanos pushed us over the
@@ -205,17 +528,80 @@ impl Add<Duration> for SuspendUnawareInstant {
                 .checked_add(nanos_carry as u64)
                 .is_none()
             {
-                SuspendUnawareInstant { secs: 0, nanos: 0 }
+                Instant::new(0, 0)
             } else {
-                SuspendUnawareInstant {
-                    secs: self.secs + rhs_secs + (nanos_carry as u64),
-                    nanos: (self.nanos + rhs_nanos) % NANOS_PER_SECOND,
-                }
+                Instant::new(
+                    self.secs + rhs_secs + (nanos_carry as u64),
+                    (self.nanos + rhs_nanos) % NANOS_PER_SECOND,
+                )
             }
         }
     }
 }
 
+/// The counterpart of [`SuspendUnawareInstant`]: a monotonic instant which
+/// **keeps counting** while the system is suspended, on every platform
+/// supported by this library.
+///
+/// On its own this behaves like most `Instant` implementations. Its purpose is
+/// to be compared against a [`SuspendUnawareInstant`] taken at the same time:
+/// the difference between the time elapsed on both clocks is the time the
+/// system spent suspended.
+///
+/// # Underlying System calls
+///
+/// |  Platform |               System call                               |
+/// |-----------|---------------------------------------------------------|
+/// | Linux     | [clock_gettime] (CLOCK_BOOTTIME)                        |
+/// | Darwin    | [clock_gettime] (CLOCK_MONOTONIC)                       |
+/// | Windows   | [QueryInterruptTimePrecise]                             |
+///
+/// [clock_gettime]: https://www.manpagez.com/man/3/clock_gettime/
+/// [QueryInterruptTimePrecise]:
+/// https://learn.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryinterrupttimeprecise
+///
+/// It shares the representation, invariants and arithmetic of
+/// [`SuspendUnawareInstant`].
+pub type SuspendAwareInstant = Instant<SuspendAware>;
+
+/// A suspend-unaware instant read from a raw clock, which follows the hardware
+/// counter and is never slewed by NTP adjustments, for measurements which need
+/// the same semantics on every platform, like latency benchmarks.
+///
+/// [`SuspendUnawareInstant`] is slewed on Linux, where `CLOCK_MONOTONIC` runs
+/// slightly faster or slower while NTP corrects the system time (by at most
+/// 0.05%). The raw clock is not corrected, so it drifts from real time by
+/// however much the hardware oscillator is off. Instants of the two clocks
+/// cannot be compared with each other.
+///
+/// # Underlying System calls
+///
+/// |  Platform |               System call                               |
+/// |-----------|---------------------------------------------------------|
+/// | Linux     | [clock_gettime] (CLOCK_MONOTONIC_RAW)                   |
+/// | Darwin    | [clock_gettime] (CLOCK_UPTIME_RAW)                      |
+/// | Windows   | [QueryUnbiasedInterruptTimePrecise]                     |
+///
+/// On Darwin and Windows, this is the same clock as [`SuspendUnawareInstant`],
+/// which is not slewed there. On Windows this is only approximately raw: the
+/// interrupt time is not adjusted by the time service, but the kernel
+/// calibrates the rate of the underlying performance counter at boot.
+///
+/// [clock_gettime]: https://www.manpagez.com/man/3/clock_gettime/
+/// [QueryUnbiasedInterruptTimePrecise]:
+/// https://learn.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryunbiasedinterrupttimeprecise
+///
+/// # Examples
+///
+/// ```
+/// use suspend_time::RawSuspendUnawareInstant;
+///
+/// let start = RawSuspendUnawareInstant::now();
+/// let sum: u64 = (0..1_000).sum();
+/// println!("{sum} took {:?}", start.elapsed());
+/// ```
+pub type RawSuspendUnawareInstant = Instant<Raw>;
+
 /// Suspend-time's equivalent of tokio's `tokio::time::error::Elapsed`.
 /// Constructing the `Elapsed` struct is impossible due to its private construct
 /// and private members. As such, we must create our own struct
@@ -246,12 +632,135 @@ where
 }
 
 /// The same API as tokio::time::sleep, except it is uses on SuspendUnawareInstant for measuring time.
-pub async fn sleep(duration: Duration) {
-    let deadline = SuspendUnawareInstant::now() + duration;
-    let mut now = SuspendUnawareInstant::now();
-    while now < deadline {
-        tokio::time::sleep(deadline - now).await;
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(SuspendUnawareInstant::now().saturating_add(duration))
+}
 
-        now = SuspendUnawareInstant::now();
+/// The same API as tokio::time::sleep_until, except the deadline is a SuspendUnawareInstant.
+pub fn sleep_until(deadline: SuspendUnawareInstant) -> Sleep {
+    Sleep {
+        deadline,
+        inner: None,
     }
 }
+
+/// Report returned by [`sleep_with_report`], describing how a sleep went.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct SleepReport {
+    /// Time that passed during the sleep, excluding system suspends. This is
+    /// never less than the requested duration.
+    pub active: Duration,
+    /// Time the system spent suspended during the sleep.
+    pub suspended: Duration,
+    /// How much longer than the requested duration the sleep took, excluding
+    /// system suspends.
+    pub lateness: Duration,
+}
+
+/// The same as [`sleep`], except it resolves to a [`SleepReport`] telling
+/// whether (and for how long) the system was suspended during the sleep, and by
+/// how much the timer overshot.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let report = suspend_time::sleep_with_report(Duration::from_millis(10)).await;
+///     assert!(report.active >= Duration::from_millis(10));
+/// }
+/// ```
+pub async fn sleep_with_report(duration: Duration) -> SleepReport {
+    let unaware_start = SuspendUnawareInstant::now();
+    let aware_start = SuspendAwareInstant::now();
+    sleep_until(unaware_start.saturating_add(duration)).await;
+
+    let active = unaware_start.elapsed();
+    let suspended = suspended_time(active, aware_start.elapsed());
+    SleepReport {
+        active,
+        suspended,
+        lateness: active.saturating_sub(duration),
+    }
+}
+
+/// Returns how long the system was suspended over a period that lasted
+/// `unaware` on the suspend-unaware clock and `aware` on the suspend-aware
+/// clock.
+///
+/// Both clocks cannot be read at the exact same time, so the result is subject
+/// to a small amount of jitter when no suspend happened.
+pub(crate) fn suspended_time(unaware: Duration, aware: Duration) -> Duration {
+    aware.saturating_sub(unaware)
+}
+
+/// Future returned by [`sleep`] and [`sleep_until`].
+///
+/// Tokio's timer keeps counting while the system is suspended on some
+/// platforms, so it may wake us up early. Every time it fires, we re-read
+/// [`SuspendUnawareInstant`] and go back to sleep for whatever is left until
+/// the deadline.
+///
+/// Like an `async fn`, the future does nothing until it is first polled, so it
+/// can be created outside of a tokio runtime.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    deadline: SuspendUnawareInstant,
+    // Created on first poll. Boxed so that Sleep is Unpin.
+    inner: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl Sleep {
+    /// Returns the instant at which the future will complete.
+    pub fn deadline(&self) -> SuspendUnawareInstant {
+        self.deadline
+    }
+
+    /// Returns true if the deadline has passed.
+    pub fn is_elapsed(&self) -> bool {
+        SuspendUnawareInstant::now() >= self.deadline
+    }
+
+    /// Resets the future to complete at `deadline` instead, even if it has
+    /// already completed.
+    pub fn reset(self: Pin<&mut Self>, deadline: SuspendUnawareInstant) {
+        let this = self.get_mut();
+        this.deadline = deadline;
+        if let Some(inner) = &mut this.inner {
+            inner
+                .as_mut()
+                .reset(tokio_deadline(deadline - SuspendUnawareInstant::now()));
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        loop {
+            let now = SuspendUnawareInstant::now();
+            if now >= this.deadline {
+                return Poll::Ready(());
+            }
+            let remaining = this.deadline - now;
+            let inner = this
+                .inner
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(remaining)));
+            ready!(inner.as_mut().poll(cx));
+            inner.as_mut().reset(tokio_deadline(remaining));
+        }
+    }
+}
+
+/// Returns the tokio instant `remaining` from now, saturating far in the
+/// future rather than overflowing, like `tokio::time::sleep` does.
+fn tokio_deadline(remaining: Duration) -> tokio::time::Instant {
+    let now = tokio::time::Instant::now();
+    now.checked_add(remaining)
+        .unwrap_or_else(|| now + FAR_FUTURE)
+}
@@ -0,0 +1,173 @@
+use crate::{SuspendUnawareInstant, NANOS_PER_SECOND};
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Default for [`set_forward_jump_threshold`].
+const DEFAULT_FORWARD_JUMP_THRESHOLD: Duration = Duration::from_secs(24 * 60 * 60);
+
+static GUARD: Guard = Guard::new();
+
+thread_local! {
+    // last value read by this thread, before clamping
+    static LAST_READ: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Counts of clock anomalies observed by the monotonicity guard, as returned
+/// by [`clock_anomalies`].
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct ClockAnomalies {
+    /// Number of times a thread read a smaller value from the clock than it
+    /// read before.
+    pub backwards_steps: u64,
+    /// Number of times the clock moved forward by more than the
+    /// [forward jump threshold](set_forward_jump_threshold) since the previous
+    /// read in the process, beyond what the wall clock moved in the meantime.
+    /// Idle gaps between reads are therefore not counted.
+    pub forward_jumps: u64,
+}
+
+/// Turns on the process-wide monotonicity guard.
+///
+/// [`SuspendUnawareInstant`] relies on the operating system for monotonicity,
+/// which buggy platforms (some virtual machines in particular) do not always
+/// deliver. Once the guard is on, [`SuspendUnawareInstant::now`] never returns
+/// an instant smaller than one it returned before, anywhere in the process,
+/// at the cost of an atomic high-water mark shared by every call, like the
+/// standard library does for `Instant` on platforms it does not trust. Clock
+/// anomalies are also counted, see [`clock_anomalies`], which costs an
+/// additional read of the wall clock per call.
+///
+/// The guard cannot be turned off again, and instants returned before it was
+/// turned on are not covered.
+pub fn enable_monotonicity_guard() {
+    GUARD.enabled.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if [`enable_monotonicity_guard`] was called.
+pub fn is_monotonicity_guard_enabled() -> bool {
+    GUARD.enabled.load(Ordering::Relaxed)
+}
+
+/// Sets how much further than the wall clock the clock may move forward
+/// between two consecutive reads in the process before it is counted as an
+/// anomaly. Defaults to one day.
+pub fn set_forward_jump_threshold(threshold: Duration) {
+    GUARD.set_forward_jump_threshold(threshold);
+}
+
+/// Returns the clock anomalies observed since the monotonicity guard was
+/// turned on. Nothing is counted while it is off.
+pub fn clock_anomalies() -> ClockAnomalies {
+    GUARD.anomalies()
+}
+
+/// Applies the monotonicity guard, if enabled, to an instant read from the
+/// clock.
+pub(crate) fn guard(instant: SuspendUnawareInstant) -> SuspendUnawareInstant {
+    if !GUARD.enabled.load(Ordering::Relaxed) {
+        return instant;
+    }
+    let wall = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| {
+            u64::try_from(since_epoch.as_nanos()).unwrap_or(u64::MAX)
+        });
+    LAST_READ.with(|last_read| GUARD.apply(instant, wall, last_read))
+}
+
+/// State of the monotonicity guard. There is a single instance for the
+/// process, but tests create their own.
+#[derive(Debug)]
+pub(crate) struct Guard {
+    enabled: AtomicBool,
+    // The following values are stored as nanoseconds, which is enough for
+    // ~584 years of uptime, so that each fits in a single atomic.
+    high_water_mark: AtomicU64,
+    // wall clock time at which the high-water mark was last raised, since the
+    // Unix epoch
+    wall_at_high_water_mark: AtomicU64,
+    forward_jump_threshold: AtomicU64,
+    backwards_steps: AtomicU64,
+    forward_jumps: AtomicU64,
+}
+
+impl Guard {
+    pub(crate) const fn new() -> Guard {
+        Guard {
+            enabled: AtomicBool::new(false),
+            high_water_mark: AtomicU64::new(0),
+            wall_at_high_water_mark: AtomicU64::new(0),
+            forward_jump_threshold: AtomicU64::new(
+                DEFAULT_FORWARD_JUMP_THRESHOLD.as_secs() * NANOS_PER_SECOND as u64,
+            ),
+            backwards_steps: AtomicU64::new(0),
+            forward_jumps: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn set_forward_jump_threshold(&self, threshold: Duration) {
+        let threshold = u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX);
+        self.forward_jump_threshold
+            .store(threshold, Ordering::Relaxed);
+    }
+
+    pub(crate) fn anomalies(&self) -> ClockAnomalies {
+        ClockAnomalies {
+            backwards_steps: self.backwards_steps.load(Ordering::Relaxed),
+            forward_jumps: self.forward_jumps.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Clamps `instant` to the high-water mark and counts anomalies. `wall` is
+    /// the wall clock time at which `instant` was read, in nanoseconds since
+    /// the Unix epoch, and `last_read` is the previous value read by the
+    /// calling thread.
+    pub(crate) fn apply(
+        &self,
+        instant: SuspendUnawareInstant,
+        wall: u64,
+        last_read: &Cell<u64>,
+    ) -> SuspendUnawareInstant {
+        let nanos = to_nanos(instant);
+        // Only a single thread can tell whether the clock really went
+        // backwards: across threads, a smaller value can simply be a read that
+        // lost a race.
+        if nanos < last_read.replace(nanos) {
+            self.backwards_steps.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let high_water_mark = self.high_water_mark.fetch_max(nanos, Ordering::Relaxed);
+        if nanos < high_water_mark {
+            return from_nanos(high_water_mark);
+        }
+        // Long gaps between reads are expected, so only the part of the
+        // advance that the wall clock does not account for is a jump. The two
+        // values are not updated together, which can only blur the estimate
+        // by the duration of a race.
+        let wall_at_high_water_mark = self.wall_at_high_water_mark.swap(wall, Ordering::Relaxed);
+        let unexplained =
+            (nanos - high_water_mark).saturating_sub(wall.saturating_sub(wall_at_high_water_mark));
+        if high_water_mark != 0 && unexplained > self.forward_jump_threshold.load(Ordering::Relaxed)
+        {
+            self.forward_jumps.fetch_add(1, Ordering::Relaxed);
+        }
+        instant
+    }
+}
+
+fn to_nanos(instant: SuspendUnawareInstant) -> u64 {
+    instant
+        .secs
+        .saturating_mul(NANOS_PER_SECOND as u64)
+        .saturating_add(instant.nanos as u64)
+}
+
+fn from_nanos(nanos: u64) -> SuspendUnawareInstant {
+    SuspendUnawareInstant::new(
+        nanos / NANOS_PER_SECOND as u64,
+        (nanos % NANOS_PER_SECOND as u64) as u32,
+    )
+}
@@ -1,13 +1,11 @@
-use crate::SuspendUnawareInstant;
-use libc::timespec;
-use std::cmp;
-
-const NANOS_PER_SECOND: u32 = 1_000_000_000;
+use super::unix::{clock_getres, clock_gettime};
+use crate::{
+    ClockInfo, ProcessCpuInstant, RawSuspendUnawareInstant, SuspendAwareInstant,
+    SuspendUnawareInstant, ThreadCpuInstant,
+};
+use std::{io, ptr, time::Duration};
 
 pub fn now() -> SuspendUnawareInstant {
-    // This excerpt of code is taken from the standard library's implementation
-    // of Instant:
-    // https://github.com/rust-lang/rust/blob/master/library/std/src/sys/pal/unix/time.rs#L260
     // https://www.manpagez.com/man/3/clock_gettime/
     //
     // CLOCK_UPTIME_RAW   clock that increments monotonically, in the same man-
@@ -15,30 +13,72 @@ pub fn now() -> SuspendUnawareInstant {
     // system is asleep.  The returned value is identical to the result of
     // mach_absolute_time() after the appropriate mach_timebase conversion is
     // applied.
-    let mut t: timespec = timespec {
-        tv_sec: 0,
-        tv_nsec: 0,
-    };
-    unsafe {
-        libc::clock_gettime(libc::CLOCK_UPTIME_RAW, &mut t);
-    }
+    clock_gettime(libc::CLOCK_UPTIME_RAW)
+}
+
+pub fn now_coarse() -> SuspendUnawareInstant {
+    // CLOCK_UPTIME_RAW_APPROX   like CLOCK_UPTIME_RAW, but reads a value
+    // cached by the system at context switch. This can be read faster, but at
+    // a loss of accuracy as it may return values that are milliseconds old.
+    clock_gettime(libc::CLOCK_UPTIME_RAW_APPROX)
+}
 
-    // NOTE: It possible for tv_sec/tv_nsec be negative in weird edge cases
-    // mentioned in the standard library.  It should NOT be possible for us,
-    // since we are polling a performance counter, but out of an ABUNDANCE
-    // of caution, we handle this case and floor to 0.  Also, nanos should
-    // be capped in size to 10^9. This is done in the standard library as
-    // well:
-    // https://github.com/rust-lang/rust/blob/9b00956e56009bab2aa15d7bff10916599e3d6d6/library/core/src/time.rs#L96
-    // NOTE: ^ that is taken from the release branch for Rust 1.78.0
-
-    t.tv_sec = cmp::max(t.tv_sec, 0);
-    t.tv_nsec = cmp::max(t.tv_nsec, 0);
-    if t.tv_nsec >= NANOS_PER_SECOND as i64 {
-        t.tv_nsec = 0;
+pub fn resolution() -> Duration {
+    clock_getres(libc::CLOCK_UPTIME_RAW)
+}
+
+pub fn coarse_resolution() -> Duration {
+    clock_getres(libc::CLOCK_UPTIME_RAW_APPROX)
+}
+
+pub fn now_suspend_aware() -> SuspendAwareInstant {
+    // CLOCK_MONOTONIC    clock that increments monotonically, tracking the
+    // time since an arbitrary point, and will continue to increment while the
+    // system is asleep.
+    clock_gettime(libc::CLOCK_MONOTONIC)
+}
+
+pub fn now_raw() -> RawSuspendUnawareInstant {
+    // CLOCK_UPTIME_RAW is already raw, see now()
+    clock_gettime(libc::CLOCK_UPTIME_RAW)
+}
+
+pub fn now_thread_cpu() -> ThreadCpuInstant {
+    clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID)
+}
+
+pub fn now_process_cpu() -> ProcessCpuInstant {
+    clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID)
+}
+
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        backend: "apple",
+        clock: "CLOCK_UPTIME_RAW",
+        resolution: resolution(),
+        suspend_excluding: true,
+        // The _RAW clocks follow mach_absolute_time(), which is not adjusted.
+        ntp_slewed: false,
     }
-    SuspendUnawareInstant {
-        secs: t.tv_sec as u64,
-        nanos: t.tv_nsec as u32, // (i64 --> u32) we know this type conversion will work since we just clamped it
+}
+
+pub fn boot_id() -> io::Result<String> {
+    // kern.bootsessionuuid is a UUID generated once at each boot, as a NUL
+    // terminated string.
+    let mut buf = [0u8; 64];
+    let mut len = buf.len();
+    let res = unsafe {
+        libc::sysctlbyname(
+            b"kern.bootsessionuuid\0".as_ptr().cast(),
+            buf.as_mut_ptr().cast(),
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if res != 0 {
+        return Err(io::Error::last_os_error());
     }
+    let boot_id = buf[..len].split(|b| *b == 0).next().unwrap_or_default();
+    Ok(String::from_utf8_lossy(boot_id).into_owned())
 }
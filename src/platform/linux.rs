@@ -0,0 +1,79 @@
+use super::unix::{clock_getres, clock_gettime};
+use crate::{
+    ClockInfo, ProcessCpuInstant, RawSuspendUnawareInstant, SuspendAwareInstant,
+    SuspendUnawareInstant, ThreadCpuInstant,
+};
+use std::{fs, io, time::Duration};
+
+pub fn now() -> SuspendUnawareInstant {
+    // https://man7.org/linux/man-pages/man2/clock_gettime.2.html
+    //
+    // CLOCK_MONOTONIC    A nonsettable system-wide clock that represents
+    // monotonic time since—as described by POSIX—"some unspecified point in
+    // the past". On Linux, that point corresponds to the number of seconds
+    // that the system has been running since it was booted.
+    //
+    // It does not count time that the system is suspended. It is the same
+    // clock the standard library uses for Instant on Linux.
+    clock_gettime(libc::CLOCK_MONOTONIC)
+}
+
+pub fn now_coarse() -> SuspendUnawareInstant {
+    // CLOCK_MONOTONIC_COARSE    A faster but less precise version of
+    // CLOCK_MONOTONIC. Use when you need very fast, but not fine-grained
+    // timestamps.
+    clock_gettime(libc::CLOCK_MONOTONIC_COARSE)
+}
+
+pub fn resolution() -> Duration {
+    clock_getres(libc::CLOCK_MONOTONIC)
+}
+
+pub fn coarse_resolution() -> Duration {
+    // The resolution of the coarse clocks is the kernel's tick (jiffy)
+    clock_getres(libc::CLOCK_MONOTONIC_COARSE)
+}
+
+pub fn now_suspend_aware() -> SuspendAwareInstant {
+    // CLOCK_BOOTTIME    A nonsettable system-wide clock that is identical to
+    // CLOCK_MONOTONIC, except that it also includes any time that the system
+    // is suspended.
+    clock_gettime(libc::CLOCK_BOOTTIME)
+}
+
+pub fn now_raw() -> RawSuspendUnawareInstant {
+    // CLOCK_MONOTONIC_RAW    Similar to CLOCK_MONOTONIC, but provides access
+    // to a raw hardware-based time that is not subject to NTP adjustments or
+    // the incremental adjustments performed by adjtime(3). This clock does
+    // not count time that the system is suspended.
+    clock_gettime(libc::CLOCK_MONOTONIC_RAW)
+}
+
+pub fn now_thread_cpu() -> ThreadCpuInstant {
+    clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID)
+}
+
+pub fn now_process_cpu() -> ProcessCpuInstant {
+    clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID)
+}
+
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        backend: "linux",
+        clock: "CLOCK_MONOTONIC",
+        resolution: resolution(),
+        suspend_excluding: true,
+        // CLOCK_MONOTONIC "is affected by the incremental adjustments performed
+        // by adjtime(3) and NTP". CLOCK_MONOTONIC_RAW is not.
+        ntp_slewed: true,
+    }
+}
+
+pub fn boot_id() -> io::Result<String> {
+    // https://man7.org/linux/man-pages/man4/random.4.html
+    //
+    // boot_id    a random string like 6fd5a44b-35f4-4ad4-a9b9-6b9be13e1fe9,
+    // generated once at each boot.
+    let boot_id = fs::read_to_string("/proc/sys/kernel/random/boot_id")?;
+    Ok(boot_id.trim_end().to_string())
+}
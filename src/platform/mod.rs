@@ -1,7 +1,12 @@
 cfg_if::cfg_if! {
     if #[cfg(target_vendor = "apple")] {
+        mod unix;
         mod apple;
         pub use self::apple::*;
+    } else if #[cfg(target_os = "linux")] {
+        mod unix;
+        mod linux;
+        pub use self::linux::*;
     } else if #[cfg(windows)] {
         mod windows;
         pub use self::windows::*;
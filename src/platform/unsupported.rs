@@ -1,9 +1,47 @@
-use std::time::Duration;
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
-pub struct SuspendUnawareInstant {}
-impl SuspendUnawareInstant {
-    pub fn now() -> SuspendUnawareInstant {
-        unimplemented!("This platform is not supported by the suspend-time library!");
-    }
+use crate::{
+    ClockInfo, ProcessCpuInstant, RawSuspendUnawareInstant, SuspendAwareInstant,
+    SuspendUnawareInstant, ThreadCpuInstant,
+};
+use std::{io, time::Duration};
+
+const UNSUPPORTED: &str = "This platform is not supported by the suspend-time library!";
+
+pub fn now() -> SuspendUnawareInstant {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn now_coarse() -> SuspendUnawareInstant {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn resolution() -> Duration {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn coarse_resolution() -> Duration {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn now_suspend_aware() -> SuspendAwareInstant {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn now_raw() -> RawSuspendUnawareInstant {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn now_thread_cpu() -> ThreadCpuInstant {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn now_process_cpu() -> ProcessCpuInstant {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn clock_info() -> ClockInfo {
+    unimplemented!("{}", UNSUPPORTED);
+}
+
+pub fn boot_id() -> io::Result<String> {
+    unimplemented!("{}", UNSUPPORTED);
 }
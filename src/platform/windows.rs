@@ -1,4 +1,8 @@
-use crate::{SuspendUnawareInstant, NANOS_PER_SECOND};
+use crate::{
+    ClockInfo, Instant, ProcessCpuInstant, RawSuspendUnawareInstant, SuspendAwareInstant,
+    SuspendUnawareInstant, ThreadCpuInstant, NANOS_PER_SECOND,
+};
+use std::{io, ptr, time::Duration};
 use windows_sys::Win32;
 
 /// As per the windows documentation, the perf count for the counter we are
@@ -15,6 +19,22 @@ fn query_unbiased_interrupt_time_precise() -> u64 {
     res
 }
 
+fn query_unbiased_interrupt_time() -> u64 {
+    let mut res: u64 = 0;
+    unsafe {
+        Win32::System::WindowsProgramming::QueryUnbiasedInterruptTime(&mut res);
+    }
+    res
+}
+
+fn query_interrupt_time_precise() -> u64 {
+    let mut res: u64 = 0;
+    unsafe {
+        Win32::System::WindowsProgramming::QueryInterruptTimePrecise(&mut res);
+    }
+    res
+}
+
 /// Calls the windows realtime api function to return the count of 100ns
 /// intervals since the system was booted, ignoring periods when the system was
 /// suspended/hibernating.    
@@ -29,13 +49,158 @@ fn query_unbiased_interrupt_time_precise() -> u64 {
 /// timer hardware directly, therefore a QueryUnbiasedInterruptTimePrecise call
 /// can be slower than a QueryUnbiasedInterruptTime call.
 pub fn now() -> SuspendUnawareInstant {
+    from_intervals(query_unbiased_interrupt_time_precise())
+}
+
+/// Same as [`now`], except it returns the unbiased interrupt time as of the
+/// last system timer tick, rather than reading the timer hardware. This is
+/// what makes QueryUnbiasedInterruptTimePrecise slower (see above).
+///
+/// Source:
+/// https://learn.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryunbiasedinterrupttime
+pub fn now_coarse() -> SuspendUnawareInstant {
+    from_intervals(query_unbiased_interrupt_time())
+}
+
+/// The precise counter is reported in 100ns intervals.
+pub fn resolution() -> Duration {
+    Duration::from_nanos(WINDOWS_PERF_INTERVAL_SIZE_NS)
+}
+
+/// The coarse counter is updated on every system timer tick, the interval of
+/// which is reported (in 100ns intervals) by GetSystemTimeAdjustment. This is
+/// 15.625ms by default.
+///
+/// Source:
+/// https://learn.microsoft.com/en-us/windows/win32/api/sysinfoapi/nf-sysinfoapi-getsystemtimeadjustment
+pub fn coarse_resolution() -> Duration {
+    let mut adjustment: u32 = 0;
+    let mut increment: u32 = 0;
+    let mut adjustment_disabled = 0;
+    unsafe {
+        Win32::System::SystemInformation::GetSystemTimeAdjustment(
+            &mut adjustment,
+            &mut increment,
+            &mut adjustment_disabled,
+        );
+    }
+    Duration::from_nanos(increment as u64 * WINDOWS_PERF_INTERVAL_SIZE_NS)
+}
+
+/// Same as [`now`], except the biased interrupt time keeps counting while the
+/// system is suspended/hibernating.
+///
+/// Source:
+/// https://learn.microsoft.com/en-us/windows/win32/api/realtimeapiset/nf-realtimeapiset-queryinterrupttimeprecise
+pub fn now_suspend_aware() -> SuspendAwareInstant {
+    from_intervals(query_interrupt_time_precise())
+}
+
+/// Same as [`now`]: the interrupt time is not adjusted by the time service, so
+/// there is no separate raw counter to read.
+pub fn now_raw() -> RawSuspendUnawareInstant {
+    from_intervals(query_unbiased_interrupt_time_precise())
+}
+
+/// The CPU time consumed by the current thread, in user and kernel mode. Both
+/// are reported in 100ns intervals, but only advance on every timer tick.
+///
+/// Source:
+/// https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getthreadtimes
+pub fn now_thread_cpu() -> ThreadCpuInstant {
+    use Win32::System::Threading::{GetCurrentThread, GetThreadTimes};
+
+    let [mut creation, mut exit, mut kernel, mut user] = [empty_filetime(); 4];
+    unsafe {
+        GetThreadTimes(
+            GetCurrentThread(),
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        );
+    }
+    from_intervals(filetime_intervals(kernel) + filetime_intervals(user))
+}
+
+/// Same as [`now_thread_cpu`], for all the threads of the current process.
+///
+/// Source:
+/// https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-getprocesstimes
+pub fn now_process_cpu() -> ProcessCpuInstant {
+    use Win32::System::Threading::{GetCurrentProcess, GetProcessTimes};
+
+    let [mut creation, mut exit, mut kernel, mut user] = [empty_filetime(); 4];
+    unsafe {
+        GetProcessTimes(
+            GetCurrentProcess(),
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        );
+    }
+    from_intervals(filetime_intervals(kernel) + filetime_intervals(user))
+}
+
+fn empty_filetime() -> Win32::Foundation::FILETIME {
+    Win32::Foundation::FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    }
+}
+
+fn filetime_intervals(filetime: Win32::Foundation::FILETIME) -> u64 {
+    ((filetime.dwHighDateTime as u64) << 32) | filetime.dwLowDateTime as u64
+}
+
+/// Converts a count of 100ns intervals into an instant.
+fn from_intervals<C>(nano_intervals: u64) -> Instant<C> {
     let nanos_per_second_u64 = NANOS_PER_SECOND as u64;
-    let nano_intervals = query_unbiased_interrupt_time_precise();
     let secs = nano_intervals / ((nanos_per_second_u64) / WINDOWS_PERF_INTERVAL_SIZE_NS);
     let nanos = ((nano_intervals % nanos_per_second_u64) * 100) % nanos_per_second_u64;
 
-    SuspendUnawareInstant {
-        secs,
-        nanos: nanos as u32,
+    Instant::new(secs, nanos as u32)
+}
+
+pub fn clock_info() -> ClockInfo {
+    ClockInfo {
+        backend: "windows",
+        clock: "QueryUnbiasedInterruptTimePrecise",
+        resolution: resolution(),
+        suspend_excluding: true,
+        // The interrupt time counts timer hardware ticks, and is not adjusted
+        // by the time service.
+        ntp_slewed: false,
+    }
+}
+
+/// The kernel's boot counter, which is incremented at each boot and stored in
+/// the registry.
+pub fn boot_id() -> io::Result<String> {
+    use Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD};
+
+    let subkey: Vec<u16> =
+        "SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Memory Management\\PrefetchParameters"
+            .encode_utf16()
+            .chain([0])
+            .collect();
+    let value: Vec<u16> = "BootId".encode_utf16().chain([0]).collect();
+    let mut boot_id: u32 = 0;
+    let mut len = std::mem::size_of::<u32>() as u32;
+    let res = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            subkey.as_ptr(),
+            value.as_ptr(),
+            RRF_RT_REG_DWORD,
+            ptr::null_mut(),
+            (&mut boot_id as *mut u32).cast(),
+            &mut len,
+        )
+    };
+    if res != Win32::Foundation::ERROR_SUCCESS {
+        return Err(io::Error::from_raw_os_error(res as i32));
     }
+    Ok(boot_id.to_string())
 }
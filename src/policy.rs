@@ -0,0 +1,125 @@
+use crate::{sleep, SuspendAwareInstant, TimedOutError};
+use std::{
+    cmp,
+    future::{self, Future},
+    time::{Duration, SystemTime},
+};
+
+/// Tokio's timer does not advance while the system is suspended on some
+/// platforms (its clock is suspend-unaware on unix), so a single long tokio
+/// sleep can wake up arbitrarily late after a resume. Clocks that count
+/// suspended time are therefore re-checked at least this often.
+pub(crate) const RECHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Selects which clock a timeout or sleep is measured with.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::TimeoutPolicy;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     // a lease held by a server keeps expiring while we are suspended
+///     let res = suspend_time::timeout_with(
+///         TimeoutPolicy::IncludeSuspend,
+///         Duration::from_millis(10),
+///         suspend_time::sleep(Duration::from_secs(1)),
+///     )
+///     .await;
+///     assert!(res.is_err());
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TimeoutPolicy {
+    /// Time does not pass while the system is suspended, as measured by
+    /// [`SuspendUnawareInstant`](crate::SuspendUnawareInstant). This is the
+    /// behaviour of [`timeout`](crate::timeout) and [`sleep`], and suits
+    /// timeouts guarding local work.
+    #[default]
+    ExcludeSuspend,
+    /// Time keeps passing while the system is suspended, as measured by
+    /// [`SuspendAwareInstant`]. Suits timeouts guarding state held by someone
+    /// else, such as a server-side session, which keeps ageing while we sleep.
+    IncludeSuspend,
+    /// Time is measured with the wall clock ([`SystemTime`]), so the deadline
+    /// also moves if the system clock is changed.
+    WallClock,
+}
+
+/// The same API as [`sleep`], except the clock is selected by `policy`.
+///
+/// Clocks which count suspended time are re-checked at least every second, so
+/// the sleep completes shortly after a resume if its deadline passed while the
+/// system was suspended. A duration too long for the clock to represent (such
+/// as `Duration::MAX`) sleeps forever.
+pub async fn sleep_with(policy: TimeoutPolicy, duration: Duration) {
+    match policy {
+        TimeoutPolicy::ExcludeSuspend => sleep(duration).await,
+        TimeoutPolicy::IncludeSuspend => {
+            let deadline = SuspendAwareInstant::now().saturating_add(duration);
+            let mut now = SuspendAwareInstant::now();
+            while now < deadline {
+                tokio::time::sleep(cmp::min(deadline - now, RECHECK_INTERVAL)).await;
+
+                now = SuspendAwareInstant::now();
+            }
+        }
+        TimeoutPolicy::WallClock => match SystemTime::now().checked_add(duration) {
+            Some(deadline) => sleep_until_wall(deadline).await,
+            // too far to be represented, i.e. never
+            None => future::pending().await,
+        },
+    }
+}
+
+/// Sleeps until the wall clock reads `deadline`, or later.
+///
+/// Unlike sleeping for `deadline - SystemTime::now()`, this wakes up on time
+/// even if the system is suspended or the system clock is changed while
+/// sleeping: the wall clock is re-checked at least every second, so the sleep
+/// completes shortly after a resume or a clock change puts `deadline` in the
+/// past. If `deadline` is already in the past, this completes immediately.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let deadline = SystemTime::now() + Duration::from_millis(10);
+///     suspend_time::sleep_until_wall(deadline).await;
+///     assert!(SystemTime::now() >= deadline);
+/// }
+/// ```
+pub async fn sleep_until_wall(deadline: SystemTime) {
+    // duration_since fails once the deadline is in the past
+    while let Ok(remaining) = deadline.duration_since(SystemTime::now()) {
+        if remaining.is_zero() {
+            break;
+        }
+        tokio::time::sleep(cmp::min(remaining, RECHECK_INTERVAL)).await;
+    }
+}
+
+/// The same API as [`timeout`](crate::timeout), except the clock is selected
+/// by `policy`.
+pub async fn timeout_with<F>(
+    policy: TimeoutPolicy,
+    duration: Duration,
+    future: F,
+) -> Result<F::Output, TimedOutError>
+where
+    F: Future,
+{
+    tokio::select! {
+        _ = sleep_with(policy, duration) => {
+            Err(TimedOutError)
+        }
+        output = future => {
+            Ok(output)
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    policy::RECHECK_INTERVAL, sleep_until, suspended_time, SuspendAwareInstant,
+    SuspendUnawareInstant, TimedOutError,
+};
+use std::{cmp, error::Error, fmt, future::Future, time::Duration};
+use tokio::time::MissedTickBehavior;
+
+/// [`suspended_time`] is subject to jitter even when the system is not
+/// suspended. Anything below this is not considered a suspend.
+const SUSPEND_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// Detects system suspends by comparing how much time passed on the
+/// suspend-aware clock against the suspend-unaware clock.
+#[derive(Debug)]
+pub(crate) struct ResumeDetector {
+    unaware: SuspendUnawareInstant,
+    aware: SuspendAwareInstant,
+}
+
+impl ResumeDetector {
+    pub(crate) fn new() -> ResumeDetector {
+        ResumeDetector {
+            unaware: SuspendUnawareInstant::now(),
+            aware: SuspendAwareInstant::now(),
+        }
+    }
+
+    /// Returns how long the system was suspended since the previous check (or
+    /// since creation), or `None` if it was not suspended.
+    pub(crate) fn check(&mut self) -> Option<Duration> {
+        let unaware = SuspendUnawareInstant::now();
+        let aware = SuspendAwareInstant::now();
+        let suspended = suspended_time(unaware - self.unaware, aware - self.aware);
+        self.unaware = unaware;
+        self.aware = aware;
+
+        (suspended >= SUSPEND_THRESHOLD).then_some(suspended)
+    }
+}
+
+/// The same API as [`timeout`](crate::timeout), except that if the system
+/// resumes from a suspend while `future` is pending, the deadline is pushed
+/// back to at least `grace` after the resume.
+///
+/// This gives network operations a chance to reconnect once the network is
+/// back, instead of failing right away because the timeout was about to expire
+/// when the system was suspended. Resumes are detected within about a second.
+/// A `duration` or `grace` too long for the clock to represent (such as
+/// `Duration::MAX`) never times out.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let res = suspend_time::timeout_with_resume_grace(
+///         Duration::from_secs(2),
+///         Duration::from_secs(10),
+///         suspend_time::sleep(Duration::from_secs(1)),
+///     )
+///     .await;
+///     assert!(res.is_ok());
+/// }
+/// ```
+pub async fn timeout_with_resume_grace<F>(
+    duration: Duration,
+    grace: Duration,
+    future: F,
+) -> Result<F::Output, TimedOutError>
+where
+    F: Future,
+{
+    let mut detector = ResumeDetector::new();
+    let deadline = sleep_until(SuspendUnawareInstant::now().saturating_add(duration));
+    let mut recheck = tokio::time::interval(RECHECK_INTERVAL);
+    recheck.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tokio::pin!(future, deadline);
+
+    loop {
+        tokio::select! {
+            output = &mut future => {
+                return Ok(output);
+            }
+            _ = &mut deadline => {
+                // The deadline and the resume may be noticed at the same time,
+                // in which case the resume wins.
+                if detector.check().is_none() {
+                    return Err(TimedOutError);
+                }
+                deadline.as_mut().reset(SuspendUnawareInstant::now().saturating_add(grace));
+            }
+            _ = recheck.tick() => {
+                if detector.check().is_some() {
+                    let extended = cmp::max(
+                        deadline.deadline(),
+                        SuspendUnawareInstant::now().saturating_add(grace),
+                    );
+                    deadline.as_mut().reset(extended);
+                }
+            }
+        }
+    }
+}
+
+/// Error returned by [`cancel_on_resume`] when the system was suspended while
+/// the future was pending.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuspendedError {
+    suspended: Duration,
+}
+
+impl SuspendedError {
+    /// Returns how long the system was suspended.
+    pub fn suspended(&self) -> Duration {
+        self.suspended
+    }
+}
+
+impl fmt::Display for SuspendedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "System was suspended for {:?}", self.suspended)
+    }
+}
+
+impl Error for SuspendedError {}
+
+/// Drives `future` to completion, unless the system is suspended while it is
+/// pending, in which case `future` is dropped and a [`SuspendedError`]
+/// reporting how long the suspend lasted is returned.
+///
+/// Connections usually do not survive a suspend, so a long request that was in
+/// flight can fail fast on resume and be retried on a fresh connection, rather
+/// than hang until its timeout. Resumes are detected within about a second.
+///
+/// # Examples
+///
+/// ```
+/// #[tokio::main]
+/// async fn main() {
+///     let res = suspend_time::cancel_on_resume(async { 1 }).await;
+///     assert_eq!(res, Ok(1));
+/// }
+/// ```
+pub async fn cancel_on_resume<F>(future: F) -> Result<F::Output, SuspendedError>
+where
+    F: Future,
+{
+    let mut detector = ResumeDetector::new();
+    let mut recheck = tokio::time::interval(RECHECK_INTERVAL);
+    recheck.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tokio::pin!(future);
+
+    loop {
+        tokio::select! {
+            output = &mut future => {
+                return Ok(output);
+            }
+            _ = recheck.tick() => {
+                if let Some(suspended) = detector.check() {
+                    return Err(SuspendedError { suspended });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    error::Error,
+    fmt,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const MINUTES_PER_HOUR: u64 = 60;
+const MINUTES_PER_DAY: u64 = 24 * MINUTES_PER_HOUR;
+/// How far ahead `next_after` searches before giving up. Leap days can be 8
+/// years apart (e.g. 2096 and 2104), so this must be longer than that.
+const SEARCH_LIMIT_DAYS: u64 = 10 * 366;
+
+/// A parsed cron expression, evaluated in UTC.
+///
+/// The expression has the five standard fields, separated by whitespace:
+///
+/// | Field        | Values |
+/// |--------------|--------|
+/// | minute       | 0-59   |
+/// | hour         | 0-23   |
+/// | day of month | 1-31   |
+/// | month        | 1-12   |
+/// | day of week  | 0-7 (0 and 7 are Sunday) |
+///
+/// Each field is a comma separated list of `*`, `N`, `A-B`, `*/S`, `A-B/S` or
+/// `N/S` (every `S` from `N`). Names such as `MON` or `JAN` are not supported.
+/// As in standard cron, if both the day of month and the day of week are
+/// restricted, a day matches when **either** matches.
+///
+/// # Examples
+///
+/// ```
+/// use suspend_time::CronSchedule;
+///
+/// // 09:30 UTC on weekdays
+/// let schedule: CronSchedule = "30 9 * * 1-5".parse().unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    // bit N is set if value N matches
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+/// Error returned when parsing an invalid [`CronSchedule`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronParseError {
+    message: String,
+}
+
+impl fmt::Display for CronParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid cron expression: {}", self.message)
+    }
+}
+
+impl Error for CronParseError {}
+
+fn parse_error(message: String) -> CronParseError {
+    CronParseError { message }
+}
+
+fn parse_number(value: &str, name: &str) -> Result<u32, CronParseError> {
+    value
+        .parse()
+        .map_err(|_| parse_error(format!("{value:?} is not a number in the {name} field")))
+}
+
+/// Parses one field into a bitmask of the values it matches.
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> Result<u64, CronParseError> {
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(parse_number(step, name)?)),
+            None => (part, None),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_number(start, name)?, parse_number(end, name)?)
+        } else {
+            let value = parse_number(range, name)?;
+            // "N/S" means every S starting from N
+            (value, if step.is_some() { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(parse_error(format!(
+                "{range:?} is out of range in the {name} field ({min}-{max})"
+            )));
+        }
+        let step = step.unwrap_or(1);
+        if step == 0 {
+            return Err(parse_error(format!("step of 0 in the {name} field")));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronParseError;
+
+    fn from_str(expression: &str) -> Result<CronSchedule, CronParseError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(parse_error(format!(
+                "expected 5 fields, found {}",
+                fields.len()
+            )));
+        };
+
+        let mut days_of_week_mask = parse_field(days_of_week, "day of week", 0, 7)?;
+        // 7 is an alias for Sunday
+        if days_of_week_mask & (1 << 7) != 0 {
+            days_of_week_mask = (days_of_week_mask & !(1 << 7)) | 1;
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(minutes, "minute", 0, 59)?,
+            hours: parse_field(hours, "hour", 0, 23)?,
+            days_of_month: parse_field(days_of_month, "day of month", 1, 31)?,
+            months: parse_field(months, "month", 1, 12)?,
+            days_of_week: days_of_week_mask,
+            days_of_month_restricted: !days_of_month.starts_with('*'),
+            days_of_week_restricted: !days_of_week.starts_with('*'),
+        })
+    }
+}
+
+impl CronSchedule {
+    /// Parses a cron expression. See [`CronSchedule`] for the syntax.
+    pub fn parse(expression: &str) -> Result<CronSchedule, CronParseError> {
+        expression.parse()
+    }
+
+    /// Returns the first time strictly after `time` matched by the schedule,
+    /// or `None` if there is none in the next ten years (e.g. `0 0 30 2 *`),
+    /// or if `time` is before the unix epoch.
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        let secs = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        // minutes since the unix epoch
+        let mut minute = secs / 60 + 1;
+        let limit = minute + SEARCH_LIMIT_DAYS * MINUTES_PER_DAY;
+
+        while minute < limit {
+            let days = minute / MINUTES_PER_DAY;
+            let (year, month, day) = civil_from_days(days);
+            if self.months & (1 << month) == 0 {
+                let (year, month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                minute = days_from_civil(year, month, 1) * MINUTES_PER_DAY;
+                continue;
+            }
+            if !self.matches_day(day, weekday(days)) {
+                minute = (days + 1) * MINUTES_PER_DAY;
+                continue;
+            }
+            let hour = (minute % MINUTES_PER_DAY) / MINUTES_PER_HOUR;
+            if self.hours & (1 << hour) == 0 {
+                minute = (minute / MINUTES_PER_HOUR + 1) * MINUTES_PER_HOUR;
+                continue;
+            }
+            if self.minutes & (1 << (minute % MINUTES_PER_HOUR)) == 0 {
+                minute += 1;
+                continue;
+            }
+            return Some(UNIX_EPOCH + Duration::from_secs(minute * 60));
+        }
+        None
+    }
+
+    fn matches_day(&self, day_of_month: u32, day_of_week: u32) -> bool {
+        let day_of_month = self.days_of_month & (1 << day_of_month) != 0;
+        let day_of_week = self.days_of_week & (1 << day_of_week) != 0;
+        if self.days_of_month_restricted && self.days_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+/// Returns the day of the week of a number of days since the unix epoch, with
+/// 0 being Sunday. The epoch was a Thursday.
+fn weekday(days: u64) -> u32 {
+    ((days + 4) % 7) as u32
+}
+
+// The two following conversions between days since the unix epoch and the
+// proleptic gregorian calendar are Howard Hinnant's algorithms, restricted to
+// dates after the epoch:
+// https://howardhinnant.github.io/date_algorithms.html
+
+/// Converts days since the unix epoch into a (year, month, day) triple.
+fn civil_from_days(days: u64) -> (u64, u32, u32) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a (year, month, day) triple into days since the unix epoch.
+fn days_from_civil(year: u64, month: u32, day: u32) -> u64 {
+    let year = year - u64::from(month <= 2);
+    let era = year / 400;
+    let yoe = year % 400;
+    let mp = u64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + u64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
@@ -0,0 +1,322 @@
+use crate::{sleep_until, sleep_until_wall, SuspendUnawareInstant};
+use std::{
+    cmp,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+use tokio::{sync::watch, task::JoinSet};
+
+mod cron;
+
+pub use self::cron::{CronParseError, CronSchedule};
+
+/// A run counts as missed when the scheduler wakes up this much later than it
+/// was due. In practice this only happens when the system was suspended (or
+/// the wall clock changed) while waiting for it.
+const MISSED_RUN_THRESHOLD: Duration = Duration::from_secs(5);
+
+/// When a job scheduled with a [`Scheduler`] runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Schedule {
+    /// Every time the cron expression matches, in UTC.
+    Cron(CronSchedule),
+    /// Repeatedly, with this much suspend-unaware time between runs. Time does
+    /// not pass while the system is suspended, so these runs are never missed.
+    /// A period too long for the clock to represent (such as `Duration::MAX`)
+    /// never runs.
+    Every(Duration),
+    /// Once, when the wall clock reaches this time.
+    At(SystemTime),
+}
+
+/// What a [`Scheduler`] does about wall-clock runs ([`Schedule::Cron`] and
+/// [`Schedule::At`]) that became due while the system was suspended.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MissedRunPolicy {
+    /// Run the job once on resume, however many runs were missed.
+    #[default]
+    RunOnce,
+    /// Run the job once on resume for every run that was missed.
+    RunAll,
+    /// Do not run the job on resume, and wait for the next scheduled run.
+    Skip,
+}
+
+/// When a job will run next, as returned by [`JobHandle::next_run`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NextRun {
+    /// The job runs at this suspend-unaware instant ([`Schedule::Every`]).
+    Active(SuspendUnawareInstant),
+    /// The job runs when the wall clock reaches this time ([`Schedule::Cron`]
+    /// and [`Schedule::At`]).
+    Wall(SystemTime),
+}
+
+impl NextRun {
+    async fn wait(self) {
+        match self {
+            NextRun::Active(instant) => sleep_until(instant).await,
+            NextRun::Wall(time) => sleep_until_wall(time).await,
+        }
+    }
+}
+
+impl Schedule {
+    /// Returns the first run of the schedule, counting from "now".
+    fn first_run(&self) -> Option<NextRun> {
+        match self {
+            Schedule::Cron(cron) => cron.next_after(SystemTime::now()).map(NextRun::Wall),
+            Schedule::Every(period) => Some(NextRun::Active(
+                SuspendUnawareInstant::now().saturating_add(*period),
+            )),
+            Schedule::At(time) => Some(NextRun::Wall(*time)),
+        }
+    }
+
+    /// Called once the run that was `due` is reached. Returns how many times
+    /// the job should run now, and when it should run next.
+    fn catch_up(&self, due: NextRun, policy: MissedRunPolicy) -> (usize, Option<NextRun>) {
+        match (self, due) {
+            (Schedule::Every(period), NextRun::Active(due)) => {
+                // If the job overran its period, run again right away, but
+                // only once. A period the clock cannot represent never comes.
+                let next = cmp::max(due.saturating_add(*period), SuspendUnawareInstant::now());
+                (1, Some(NextRun::Active(next)))
+            }
+            (Schedule::Cron(cron), NextRun::Wall(due)) => {
+                let now = SystemTime::now();
+                let mut last = due;
+                let mut due_runs = 1;
+                while let Some(run) = cron.next_after(last).filter(|run| *run <= now) {
+                    last = run;
+                    due_runs += 1;
+                }
+                let runs = runs_after_missing(due, now, due_runs, policy);
+                (runs, cron.next_after(last).map(NextRun::Wall))
+            }
+            (Schedule::At(_), NextRun::Wall(due)) => {
+                (runs_after_missing(due, SystemTime::now(), 1, policy), None)
+            }
+            _ => unreachable!("next run does not match the schedule"),
+        }
+    }
+}
+
+/// Returns how many times to run a job which had `due_runs` runs due since
+/// `due`.
+fn runs_after_missing(
+    due: SystemTime,
+    now: SystemTime,
+    due_runs: usize,
+    policy: MissedRunPolicy,
+) -> usize {
+    let missed = now
+        .duration_since(due)
+        .is_ok_and(|late| late > MISSED_RUN_THRESHOLD);
+    if !missed {
+        return 1;
+    }
+    match policy {
+        MissedRunPolicy::RunOnce => 1,
+        MissedRunPolicy::RunAll => due_runs,
+        MissedRunPolicy::Skip => 0,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum JobControl {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+#[derive(Debug)]
+struct JobShared {
+    control: watch::Sender<JobControl>,
+    next_run: Mutex<Option<NextRun>>,
+}
+
+/// A handle to a job added to a [`Scheduler`], used to inspect, pause and
+/// cancel it. Dropping the handle does not cancel the job.
+#[derive(Clone, Debug)]
+pub struct JobHandle {
+    shared: Arc<JobShared>,
+}
+
+impl JobHandle {
+    /// Returns when the job will run next, or `None` if it is paused, cancelled
+    /// or has no runs left.
+    pub fn next_run(&self) -> Option<NextRun> {
+        *self.shared.next_run.lock().unwrap()
+    }
+
+    /// Stops running the job until [`JobHandle::resume`] is called. Runs that
+    /// would have happened while paused are skipped. A run which is already in
+    /// progress is not interrupted.
+    pub fn pause(&self) {
+        self.shared.control.send_if_modified(|control| {
+            let modified = *control == JobControl::Running;
+            if modified {
+                *control = JobControl::Paused;
+            }
+            modified
+        });
+    }
+
+    /// Resumes a paused job. Its schedule restarts from "now".
+    pub fn resume(&self) {
+        self.shared.control.send_if_modified(|control| {
+            let modified = *control == JobControl::Paused;
+            if modified {
+                *control = JobControl::Running;
+            }
+            modified
+        });
+    }
+
+    /// Cancels the job for good. A run which is already in progress is not
+    /// interrupted.
+    pub fn cancel(&self) {
+        self.shared.control.send_replace(JobControl::Cancelled);
+    }
+
+    /// Returns true if the job is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.shared.control.borrow() == JobControl::Paused
+    }
+
+    /// Returns true if the job was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.shared.control.borrow() == JobControl::Cancelled
+    }
+}
+
+/// Runs recurring jobs on the tokio runtime.
+///
+/// Each job has a [`Schedule`], which is either measured in suspend-unaware
+/// time or with the wall clock, and a [`MissedRunPolicy`] deciding what happens
+/// to wall-clock runs that were due while the system was suspended. A job's
+/// runs never overlap: if a run takes longer than the schedule, the next run
+/// waits for it to finish.
+///
+/// Dropping the scheduler cancels all of its jobs.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::{MissedRunPolicy, Schedule, Scheduler};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut scheduler = Scheduler::new();
+///     let reminder = scheduler.add(
+///         Schedule::Cron("0 9 * * 1-5".parse().unwrap()),
+///         MissedRunPolicy::RunOnce,
+///         || async { println!("sync time!") },
+///     );
+///     let heartbeat = scheduler.add(
+///         Schedule::Every(Duration::from_secs(30)),
+///         MissedRunPolicy::Skip,
+///         || async { println!("still alive") },
+///     );
+///
+///     assert!(reminder.next_run().is_some());
+///     heartbeat.pause();
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    jobs: JoinSet<()>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler without any jobs.
+    pub fn new() -> Scheduler {
+        Scheduler::default()
+    }
+
+    /// Adds a job which calls `job` and awaits the returned future on every
+    /// run of `schedule`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn add<F, Fut>(&mut self, schedule: Schedule, policy: MissedRunPolicy, job: F) -> JobHandle
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (control, receiver) = watch::channel(JobControl::Running);
+        let shared = Arc::new(JobShared {
+            control,
+            next_run: Mutex::new(schedule.first_run()),
+        });
+        self.jobs
+            .spawn(run_job(schedule, policy, job, receiver, shared.clone()));
+        JobHandle { shared }
+    }
+
+    /// Cancels every job and waits for them to shut down, interrupting runs
+    /// that are in progress.
+    pub async fn shutdown(&mut self) {
+        self.jobs.shutdown().await;
+    }
+}
+
+async fn run_job<F, Fut>(
+    schedule: Schedule,
+    policy: MissedRunPolicy,
+    mut job: F,
+    mut control: watch::Receiver<JobControl>,
+    shared: Arc<JobShared>,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let set_next_run = |next_run| *shared.next_run.lock().unwrap() = next_run;
+    let mut next_run = schedule.first_run();
+
+    loop {
+        let state = *control.borrow_and_update();
+        match state {
+            JobControl::Running => {}
+            JobControl::Paused => {
+                set_next_run(None);
+                if control.changed().await.is_err() {
+                    break;
+                }
+                next_run = schedule.first_run();
+                continue;
+            }
+            JobControl::Cancelled => break,
+        }
+
+        let Some(due) = next_run else {
+            break;
+        };
+        set_next_run(Some(due));
+        tokio::select! {
+            _ = due.wait() => {}
+            res = control.changed() => {
+                if res.is_err() {
+                    break;
+                }
+                continue;
+            }
+        }
+
+        let (runs, next) = schedule.catch_up(due, policy);
+        for _ in 0..runs {
+            job().await;
+        }
+        next_run = next;
+        // A run that is already due does not yield to the runtime, so a
+        // period shorter than a run would otherwise never let other tasks
+        // (or this job's own cancellation) run.
+        tokio::task::yield_now().await;
+    }
+
+    set_next_run(None);
+}
@@ -0,0 +1,119 @@
+//! Serialization of [`SuspendUnawareInstant`], available with the `serde`
+//! feature.
+//!
+//! An instant only makes sense during the boot it was taken in: after a
+//! reboot, the clock restarts from zero. Instants are therefore serialized
+//! along with the [`boot_id`] of the system, and deserializing an instant
+//! taken during another boot fails with an error. Fields using
+//! [`expire_on_reboot`] instead deserialize such instants as expired.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use suspend_time::SuspendUnawareInstant;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct PendingRetry {
+//!     // fails to load after a reboot
+//!     not_before: SuspendUnawareInstant,
+//!     // loads as already expired after a reboot
+//!     #[serde(with = "suspend_time::serde::expire_on_reboot")]
+//!     deadline: SuspendUnawareInstant,
+//! }
+//! ```
+
+use crate::{boot_id, SuspendUnawareInstant, NANOS_PER_SECOND};
+use ::serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The serialized form of an instant.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "SuspendUnawareInstant")]
+struct Persisted {
+    boot_id: String,
+    secs: u64,
+    nanos: u32,
+}
+
+impl Serialize for SuspendUnawareInstant {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let boot_id = boot_id()
+            .map_err(|e| ser::Error::custom(format_args!("failed to read the boot id: {e}")))?;
+        Persisted {
+            boot_id,
+            secs: self.secs,
+            nanos: self.nanos,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SuspendUnawareInstant {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (instant, boot_id) = deserialize_with_boot_id(deserializer)?;
+        match boot_id {
+            BootId::Current => Ok(instant),
+            BootId::Other { persisted, current } => Err(de::Error::custom(format_args!(
+                "instant was taken during another boot of the system \
+                 (boot id {persisted}, current boot id {current})"
+            ))),
+        }
+    }
+}
+
+enum BootId {
+    Current,
+    Other { persisted: String, current: String },
+}
+
+fn deserialize_with_boot_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<(SuspendUnawareInstant, BootId), D::Error> {
+    let persisted = Persisted::deserialize(deserializer)?;
+    if persisted.nanos >= NANOS_PER_SECOND {
+        return Err(de::Error::invalid_value(
+            de::Unexpected::Unsigned(persisted.nanos.into()),
+            &"less than 1000000000 nanoseconds",
+        ));
+    }
+    let current = boot_id()
+        .map_err(|e| de::Error::custom(format_args!("failed to read the boot id: {e}")))?;
+
+    let instant = SuspendUnawareInstant::new(persisted.secs, persisted.nanos);
+    if persisted.boot_id == current {
+        Ok((instant, BootId::Current))
+    } else {
+        let persisted = persisted.boot_id;
+        Ok((instant, BootId::Other { persisted, current }))
+    }
+}
+
+/// Serializes a [`SuspendUnawareInstant`] like its `Serialize` implementation
+/// does, but deserializes an instant taken during another boot as the earliest
+/// possible instant, rather than failing. A deadline loaded this way has
+/// already passed.
+///
+/// Use with `#[serde(with = "suspend_time::serde::expire_on_reboot")]`.
+pub mod expire_on_reboot {
+    use super::{deserialize_with_boot_id, BootId};
+    use crate::SuspendUnawareInstant;
+    use ::serde::{Deserializer, Serialize, Serializer};
+
+    /// See the [module documentation](self).
+    pub fn serialize<S: Serializer>(
+        instant: &SuspendUnawareInstant,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        instant.serialize(serializer)
+    }
+
+    /// See the [module documentation](self).
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SuspendUnawareInstant, D::Error> {
+        match deserialize_with_boot_id(deserializer)? {
+            (instant, BootId::Current) => Ok(instant),
+            (_, BootId::Other { .. }) => Ok(SuspendUnawareInstant::new(0, 0)),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::{cmp::Ordering, ops::Neg, time::Duration};
+
+/// A [`Duration`] which can be negative, as returned by
+/// [`SuspendUnawareInstant::signed_duration_since`](crate::SuspendUnawareInstant::signed_duration_since).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::SuspendUnawareInstant;
+///
+/// let due = SuspendUnawareInstant::now() + Duration::from_secs(1);
+/// let fired = SuspendUnawareInstant::now();
+///
+/// let lateness = fired.signed_duration_since(due);
+/// assert!(lateness.is_negative()); // fired early
+/// ```
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default, Hash)]
+pub struct SignedDuration {
+    magnitude: Duration,
+    negative: bool, // invariant: false if magnitude is zero
+}
+
+impl SignedDuration {
+    /// A duration of zero.
+    pub const ZERO: SignedDuration = SignedDuration {
+        magnitude: Duration::ZERO,
+        negative: false,
+    };
+
+    /// Creates a positive duration (or zero).
+    pub fn positive(magnitude: Duration) -> SignedDuration {
+        SignedDuration {
+            magnitude,
+            negative: false,
+        }
+    }
+
+    /// Creates a negative duration (or zero).
+    pub fn negative(magnitude: Duration) -> SignedDuration {
+        SignedDuration {
+            magnitude,
+            negative: !magnitude.is_zero(),
+        }
+    }
+
+    /// Returns true if the duration is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the duration without its sign.
+    pub fn abs(&self) -> Duration {
+        self.magnitude
+    }
+
+    /// Returns how the duration compares to zero.
+    pub fn signum(&self) -> Ordering {
+        if self.negative {
+            Ordering::Less
+        } else if self.magnitude.is_zero() {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        }
+    }
+
+    /// Returns the total number of nanoseconds, which never overflows.
+    pub fn as_nanos(&self) -> i128 {
+        // at most u64::MAX seconds, which fits easily
+        let nanos = self.magnitude.as_nanos() as i128;
+        if self.negative {
+            -nanos
+        } else {
+            nanos
+        }
+    }
+}
+
+impl PartialOrd for SignedDuration {
+    fn partial_cmp(&self, other: &SignedDuration) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SignedDuration {
+    fn cmp(&self, other: &SignedDuration) -> Ordering {
+        self.as_nanos().cmp(&other.as_nanos())
+    }
+}
+
+impl Neg for SignedDuration {
+    type Output = SignedDuration;
+
+    fn neg(self) -> SignedDuration {
+        if self.negative {
+            SignedDuration::positive(self.magnitude)
+        } else {
+            SignedDuration::negative(self.magnitude)
+        }
+    }
+}
+
+impl From<Duration> for SignedDuration {
+    fn from(duration: Duration) -> SignedDuration {
+        SignedDuration::positive(duration)
+    }
+}
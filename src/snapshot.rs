@@ -0,0 +1,142 @@
+use crate::{SuspendAwareInstant, SuspendUnawareInstant};
+use std::time::{Duration, SystemTime};
+
+/// A snapshot is retried if reading the clocks took longer than this, e.g.
+/// because the thread was preempted in the middle.
+const MAX_READ_GAP: Duration = Duration::from_micros(100);
+/// How many times a snapshot is retried before settling for the tightest one.
+const MAX_ATTEMPTS: usize = 8;
+
+/// The suspend-unaware, suspend-aware and wall clocks, read at (nearly) the
+/// same moment.
+///
+/// A snapshot relates instants of each clock to the others, e.g. to turn a
+/// [`SuspendUnawareInstant`] into a human readable time for a log line, with
+/// [`ClockSnapshot::wall_time_of`]. Since the monotonic clocks are shared by
+/// every process on the system, snapshots taken by different processes also
+/// relate their timestamps to each other.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::{ClockSnapshot, SuspendUnawareInstant};
+///
+/// let event = SuspendUnawareInstant::now();
+/// // ...
+/// let snapshot = ClockSnapshot::now();
+/// println!("event happened at {:?}", snapshot.wall_time_of(event).unwrap());
+/// ```
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct ClockSnapshot {
+    suspend_unaware: SuspendUnawareInstant,
+    suspend_aware: SuspendAwareInstant,
+    wall: SystemTime,
+    uncertainty: Duration,
+}
+
+impl ClockSnapshot {
+    /// Reads the three clocks back-to-back.
+    ///
+    /// The reads are retried if they are too far apart (more than 100µs,
+    /// which only happens if the thread is preempted in the middle), and the
+    /// tightest of a few attempts is kept. See [`ClockSnapshot::uncertainty`].
+    pub fn now() -> ClockSnapshot {
+        let mut best = ClockSnapshot::read();
+        for _ in 1..MAX_ATTEMPTS {
+            if best.uncertainty <= MAX_READ_GAP {
+                break;
+            }
+            let snapshot = ClockSnapshot::read();
+            if snapshot.uncertainty < best.uncertainty {
+                best = snapshot;
+            }
+        }
+        best
+    }
+
+    fn read() -> ClockSnapshot {
+        let before = SuspendUnawareInstant::now();
+        let suspend_aware = SuspendAwareInstant::now();
+        let wall = SystemTime::now();
+        let after = SuspendUnawareInstant::now();
+
+        let uncertainty = after - before;
+        ClockSnapshot {
+            suspend_unaware: before + uncertainty / 2,
+            suspend_aware,
+            wall,
+            uncertainty,
+        }
+    }
+
+    /// Returns the reading of the suspend-unaware clock.
+    pub fn suspend_unaware(&self) -> SuspendUnawareInstant {
+        self.suspend_unaware
+    }
+
+    /// Returns the reading of the suspend-aware clock.
+    pub fn suspend_aware(&self) -> SuspendAwareInstant {
+        self.suspend_aware
+    }
+
+    /// Returns the reading of the wall clock.
+    pub fn wall(&self) -> SystemTime {
+        self.wall
+    }
+
+    /// Returns how long reading the clocks took. The readings may be off from
+    /// each other by up to this much.
+    pub fn uncertainty(&self) -> Duration {
+        self.uncertainty
+    }
+
+    /// Returns how long the system has spent suspended since it booted, as of
+    /// this snapshot.
+    pub fn suspended_since_boot(&self) -> Duration {
+        // both clocks count from boot
+        let aware = SuspendUnawareInstant::new(self.suspend_aware.secs, self.suspend_aware.nanos);
+        aware - self.suspend_unaware
+    }
+
+    /// Returns how long the system spent suspended between `earlier` and this
+    /// snapshot, or zero if `earlier` is not earlier.
+    pub fn suspended_since(&self, earlier: &ClockSnapshot) -> Duration {
+        self.suspended_since_boot()
+            .saturating_sub(earlier.suspended_since_boot())
+    }
+
+    /// Estimates the wall-clock time at `instant`, assuming that the system was
+    /// not suspended and that the wall clock was not changed between `instant`
+    /// and this snapshot.
+    ///
+    /// If the system was suspended in between, `instant` actually happened
+    /// earlier than this, by up to the time spent suspended (see
+    /// [`ClockSnapshot::earliest_wall_time_of`]). This also works for instants
+    /// after the snapshot, under the same assumptions.
+    ///
+    /// Returns `None` if the result is too far from the snapshot to be
+    /// represented by a `SystemTime`.
+    pub fn wall_time_of(&self, instant: SuspendUnawareInstant) -> Option<SystemTime> {
+        if instant <= self.suspend_unaware {
+            self.wall.checked_sub(self.suspend_unaware - instant)
+        } else {
+            self.wall.checked_add(instant - self.suspend_unaware)
+        }
+    }
+
+    /// Returns the earliest wall-clock time `instant` can have happened at,
+    /// if the system spent all of its suspended time since boot between
+    /// `instant` and this snapshot. [`ClockSnapshot::wall_time_of`] is the
+    /// latest, so the two bound the actual time.
+    ///
+    /// When a snapshot taken before `instant` is available, subtracting
+    /// [`ClockSnapshot::suspended_since`] from `wall_time_of` gives a tighter
+    /// bound.
+    ///
+    /// Returns `None` if the result cannot be represented by a `SystemTime`.
+    pub fn earliest_wall_time_of(&self, instant: SuspendUnawareInstant) -> Option<SystemTime> {
+        self.wall_time_of(instant)?
+            .checked_sub(self.suspended_since_boot())
+    }
+}
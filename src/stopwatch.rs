@@ -0,0 +1,157 @@
+use crate::{suspended_time, SuspendAwareInstant, SuspendUnawareInstant};
+use std::time::Duration;
+
+/// A single lap recorded by a [`Stopwatch`].
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct Lap {
+    /// Time that passed while the stopwatch was running, excluding system
+    /// suspends.
+    pub active: Duration,
+    /// Time the system spent suspended while the stopwatch was running.
+    pub suspended: Duration,
+}
+
+/// A stopwatch built on [`SuspendUnawareInstant`], with pause/resume and laps.
+///
+/// [`Stopwatch::elapsed`] only counts time that passed while the stopwatch was
+/// running and the system was not suspended. Time spent suspended while running
+/// is measured separately with [`SuspendAwareInstant`], and reported by
+/// [`Stopwatch::suspended`] and each [`Lap`].
+///
+/// # Examples
+///
+/// ```
+/// use std::{thread, time::Duration};
+/// use suspend_time::Stopwatch;
+///
+/// let mut stopwatch = Stopwatch::start();
+/// thread::sleep(Duration::from_millis(10));
+/// stopwatch.pause();
+/// // time spent paused is not counted
+/// thread::sleep(Duration::from_millis(10));
+/// stopwatch.resume();
+/// let lap = stopwatch.lap();
+///
+/// assert!(lap.active >= Duration::from_millis(10));
+/// assert_eq!(stopwatch.laps(), &[lap]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Stopwatch {
+    // start of the current running segment, None while paused
+    running_since: Option<(SuspendUnawareInstant, SuspendAwareInstant)>,
+    // totals of the finished segments
+    active: Duration,
+    suspended: Duration,
+    // portion of the totals that belongs to the current lap
+    lap: Lap,
+    laps: Vec<Lap>,
+}
+
+impl Stopwatch {
+    /// Creates a paused stopwatch with no elapsed time.
+    pub fn new() -> Stopwatch {
+        Stopwatch::default()
+    }
+
+    /// Creates a stopwatch which is running from "now".
+    pub fn start() -> Stopwatch {
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.resume();
+        stopwatch
+    }
+
+    /// Returns true if the stopwatch is running (not paused).
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Stops counting time until [`Stopwatch::resume`] is called. Pausing a
+    /// paused stopwatch has no effect.
+    pub fn pause(&mut self) {
+        self.flush();
+        self.running_since = None;
+    }
+
+    /// Starts counting time again after [`Stopwatch::pause`]. Resuming a
+    /// running stopwatch has no effect.
+    pub fn resume(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(now());
+        }
+    }
+
+    /// Ends the current lap and starts a new one, returning the lap that ended.
+    /// A lap covers the time since the previous lap (or since the stopwatch
+    /// started), excluding pauses.
+    pub fn lap(&mut self) -> Lap {
+        self.flush();
+        let lap = std::mem::take(&mut self.lap);
+        self.laps.push(lap);
+        lap
+    }
+
+    /// Clears the elapsed time and all laps. A running stopwatch keeps running
+    /// from "now", and a paused one stays paused.
+    pub fn reset(&mut self) {
+        let running = self.is_running();
+        *self = Stopwatch::new();
+        if running {
+            self.resume();
+        }
+    }
+
+    /// Returns the total time counted by the stopwatch, excluding pauses and
+    /// system suspends.
+    pub fn elapsed(&self) -> Duration {
+        self.active + self.current_segment().active
+    }
+
+    /// Returns the total time the system spent suspended while the stopwatch
+    /// was running.
+    pub fn suspended(&self) -> Duration {
+        self.suspended + self.current_segment().suspended
+    }
+
+    /// Returns the laps recorded so far, oldest first.
+    pub fn laps(&self) -> &[Lap] {
+        &self.laps
+    }
+
+    /// Returns the time counted in the current running segment, without
+    /// modifying the stopwatch.
+    fn current_segment(&self) -> Lap {
+        self.segment_until(now())
+    }
+
+    fn segment_until(
+        &self,
+        (unaware_now, aware_now): (SuspendUnawareInstant, SuspendAwareInstant),
+    ) -> Lap {
+        match self.running_since {
+            Some((unaware_start, aware_start)) => {
+                let active = unaware_now - unaware_start;
+                let suspended = suspended_time(active, aware_now - aware_start);
+                Lap { active, suspended }
+            }
+            None => Lap::default(),
+        }
+    }
+
+    /// Moves the current running segment into the totals and, if running,
+    /// starts a new segment from "now".
+    fn flush(&mut self) {
+        if self.running_since.is_some() {
+            let now = now();
+            let segment = self.segment_until(now);
+            self.active += segment.active;
+            self.suspended += segment.suspended;
+            self.lap.active += segment.active;
+            self.lap.suspended += segment.suspended;
+            self.running_since = Some(now);
+        }
+    }
+}
+
+fn now() -> (SuspendUnawareInstant, SuspendAwareInstant) {
+    (SuspendUnawareInstant::now(), SuspendAwareInstant::now())
+}
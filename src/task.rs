@@ -0,0 +1,165 @@
+use crate::{timeout, TimedOutError};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::task::{AbortHandle, JoinError, JoinHandle, JoinSet};
+
+/// Spawns `future` as a tokio task which is aborted once `duration` of
+/// suspend-unaware time has passed.
+///
+/// Unlike wrapping a [`JoinHandle`] in [`timeout`], the deadline applies to
+/// the task itself: when it fires, `future` is dropped inside the task, so no
+/// work runs past its budget even if the returned handle is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let handle = suspend_time::spawn_with_timeout(
+///         Duration::from_millis(10),
+///         suspend_time::sleep(Duration::from_secs(60)),
+///     );
+///
+///     assert!(handle.await.unwrap().is_err());
+/// }
+/// ```
+pub fn spawn_with_timeout<F>(duration: Duration, future: F) -> TimeoutJoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    TimeoutJoinHandle {
+        inner: tokio::spawn(timeout(duration, future)),
+    }
+}
+
+/// An owned permission to join on a task spawned with [`spawn_with_timeout`].
+///
+/// Awaiting the handle resolves to `Ok(Err(TimedOutError))` if the task was
+/// aborted by its deadline, and to `Err(JoinError)` if it panicked or was
+/// aborted through the handle. Like tokio's [`JoinHandle`], dropping the handle
+/// detaches the task rather than aborting it.
+#[derive(Debug)]
+pub struct TimeoutJoinHandle<T> {
+    inner: JoinHandle<Result<T, TimedOutError>>,
+}
+
+impl<T> TimeoutJoinHandle<T> {
+    /// Aborts the task before its deadline.
+    pub fn abort(&self) {
+        self.inner.abort();
+    }
+
+    /// Returns an [`AbortHandle`] which can abort the task without owning the
+    /// handle.
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.inner.abort_handle()
+    }
+
+    /// Returns true if the task has finished, timed out, or been aborted.
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+}
+
+impl<T> Future for TimeoutJoinHandle<T> {
+    type Output = Result<Result<T, TimedOutError>, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.inner).poll(cx)
+    }
+}
+
+/// A collection of tasks, each with its own suspend-unaware deadline.
+///
+/// This is tokio's [`JoinSet`], except each task is spawned as with
+/// [`spawn_with_timeout`], and [`DeadlineJoinSet::join_next`] reports tasks
+/// which hit their deadline as `Ok(Err(TimedOutError))`, distinct from tasks
+/// which completed, panicked or were aborted.
+///
+/// Dropping the set aborts every task in it.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::DeadlineJoinSet;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut set = DeadlineJoinSet::new();
+///     set.spawn(Duration::from_millis(10), async {
+///         suspend_time::sleep(Duration::from_secs(60)).await;
+///         "slow"
+///     });
+///     set.spawn(Duration::from_secs(60), async { "fast" });
+///
+///     assert_eq!(set.join_next().await.unwrap().unwrap(), Ok("fast"));
+///     assert!(set.join_next().await.unwrap().unwrap().is_err());
+///     assert!(set.join_next().await.is_none());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DeadlineJoinSet<T> {
+    inner: JoinSet<Result<T, TimedOutError>>,
+}
+
+impl<T> Default for DeadlineJoinSet<T> {
+    fn default() -> Self {
+        DeadlineJoinSet::new()
+    }
+}
+
+impl<T> DeadlineJoinSet<T> {
+    /// Creates an empty set.
+    pub fn new() -> DeadlineJoinSet<T> {
+        DeadlineJoinSet {
+            inner: JoinSet::new(),
+        }
+    }
+
+    /// Returns the number of tasks in the set, including tasks that finished
+    /// but have not been joined yet.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the set contains no tasks.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<T: Send + 'static> DeadlineJoinSet<T> {
+    /// Spawns `future` into the set, aborting it once `duration` of
+    /// suspend-unaware time has passed.
+    pub fn spawn<F>(&mut self, duration: Duration, future: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.inner.spawn(timeout(duration, future))
+    }
+
+    /// Waits for the next task in the set to finish, in completion order.
+    /// Returns `None` if the set is empty.
+    pub async fn join_next(&mut self) -> Option<Result<Result<T, TimedOutError>, JoinError>> {
+        self.inner.join_next().await
+    }
+
+    /// Aborts every task in the set. Aborted tasks are still returned by
+    /// [`DeadlineJoinSet::join_next`], as cancelled [`JoinError`]s.
+    pub fn abort_all(&mut self) {
+        self.inner.abort_all();
+    }
+
+    /// Aborts every task in the set and waits for them to shut down.
+    pub async fn shutdown(&mut self) {
+        self.inner.shutdown().await;
+    }
+}
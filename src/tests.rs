@@ -1,9 +1,21 @@
-use crate::{SuspendUnawareInstant, TimedOutError, NANOS_PER_SECOND};
+use crate::{
+    AtomicCompactInstant, CachedClock, ClockAnomalies, ClockSource, CompactInstant,
+    CompactInstantOverflowError, CronSchedule, DeadlineJoinSet, MissedRunPolicy, NextRun,
+    ProcessCpuInstant, ProcessCpuTime, Raw, RawSuspendUnawareInstant, Schedule, Scheduler,
+    SignedDuration, Stopwatch, SuspendAware, SuspendAwareInstant, SuspendUnaware,
+    SuspendUnawareInstant, ThreadCpuInstant, ThreadCpuTime, TimedOutError, TimeoutPolicy,
+    TimeoutStream, Watchdog, NANOS_PER_SECOND,
+};
 use futures::future::join_all;
 use std::{
     cmp::Ordering,
-    time::{Duration, Instant},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 // Locally, this should pass with a 10ms tolerance. However, in circleci
 // this is flaky even at 100ms.
@@ -11,7 +23,7 @@ const TOLERANCE_MS: u64 = 1000;
 const TOLERANCE_MS_U128: u128 = TOLERANCE_MS as u128;
 
 fn create_instant(secs: u64, nanos: u32) -> SuspendUnawareInstant {
-    SuspendUnawareInstant { secs, nanos }
+    SuspendUnawareInstant::new(secs, nanos)
 }
 
 /// Testing that SuspendUnawareInstant is within a fixed tolerance of std's
@@ -201,3 +213,1112 @@ async fn stress_test() {
     join_all(futures).await;
     assert!(start.elapsed() < Duration::from_secs(1));
 }
+
+// A watchdog that is never fed should starve after its period.
+#[tokio::test]
+async fn watchdog_starves_test() {
+    let period = Duration::from_millis(200);
+    let watchdog = Watchdog::new(period);
+    let start = Instant::now();
+
+    let res = crate::timeout(
+        period + Duration::from_millis(TOLERANCE_MS),
+        watchdog.expired(),
+    )
+    .await;
+
+    assert!(res.is_ok());
+    assert!(start.elapsed() >= period);
+    assert!(watchdog.is_expired());
+}
+
+// A period the clock cannot represent should never starve, rather than wrap
+// around to an instant in the past.
+#[tokio::test]
+async fn watchdog_max_period_test() {
+    let watchdog = Watchdog::new(Duration::MAX);
+    assert!(!watchdog.is_expired());
+
+    let res = crate::timeout(Duration::from_millis(50), watchdog.expired()).await;
+    assert!(res.is_err());
+}
+
+// A watchdog that is fed more often than its period should never starve, and
+// its callback should not run.
+#[tokio::test]
+async fn watchdog_fed_test() {
+    let watchdog = Watchdog::new(Duration::from_millis(300));
+    let fired = Arc::new(AtomicBool::new(false));
+    let callback = {
+        let fired = fired.clone();
+        watchdog.on_expire(move || fired.store(true, AtomicOrdering::SeqCst))
+    };
+
+    for _ in 0..10 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        watchdog.feed();
+    }
+
+    assert!(!watchdog.is_expired());
+    assert!(!fired.load(AtomicOrdering::SeqCst));
+
+    // once feeding stops, the callback fires
+    let res = crate::timeout(Duration::from_millis(300 + TOLERANCE_MS), callback).await;
+    assert!(res.is_ok());
+    assert!(fired.load(AtomicOrdering::SeqCst));
+}
+
+#[test]
+fn stopwatch_pause_test() {
+    let mut stopwatch = Stopwatch::start();
+    std::thread::sleep(Duration::from_millis(100));
+    stopwatch.pause();
+    let paused_elapsed = stopwatch.elapsed();
+    std::thread::sleep(Duration::from_millis(100));
+
+    // no time is counted while paused
+    assert!(!stopwatch.is_running());
+    assert_eq!(stopwatch.elapsed(), paused_elapsed);
+    assert!(paused_elapsed >= Duration::from_millis(100));
+    assert!(paused_elapsed.as_millis().abs_diff(100) < TOLERANCE_MS_U128);
+
+    stopwatch.resume();
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(stopwatch.elapsed() >= paused_elapsed + Duration::from_millis(100));
+}
+
+#[test]
+fn stopwatch_laps_test() {
+    let mut stopwatch = Stopwatch::start();
+    std::thread::sleep(Duration::from_millis(50));
+    let first = stopwatch.lap();
+    std::thread::sleep(Duration::from_millis(100));
+    stopwatch.pause();
+    let second = stopwatch.lap();
+
+    assert!(first.active >= Duration::from_millis(50));
+    assert!(second.active >= Duration::from_millis(100));
+    assert_eq!(stopwatch.laps(), &[first, second]);
+    // laps partition the elapsed time
+    assert_eq!(first.active + second.active, stopwatch.elapsed());
+    assert_eq!(first.suspended + second.suspended, stopwatch.suspended());
+
+    stopwatch.reset();
+    assert!(!stopwatch.is_running());
+    assert_eq!(stopwatch.elapsed(), Duration::ZERO);
+    assert!(stopwatch.laps().is_empty());
+}
+
+// The task itself must be aborted at the deadline, not just detached from its
+// handle.
+#[tokio::test]
+async fn spawn_with_timeout_aborts_test() {
+    let finished = Arc::new(AtomicBool::new(false));
+    let handle = {
+        let finished = finished.clone();
+        crate::spawn_with_timeout(Duration::from_millis(100), async move {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            finished.store(true, AtomicOrdering::SeqCst);
+        })
+    };
+
+    assert_eq!(handle.await.unwrap(), Err(TimedOutError));
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert!(!finished.load(AtomicOrdering::SeqCst));
+
+    let handle = crate::spawn_with_timeout(Duration::from_secs(1), async { 42 });
+    assert_eq!(handle.await.unwrap(), Ok(42));
+}
+
+#[tokio::test]
+async fn deadline_join_set_test() {
+    let mut set = DeadlineJoinSet::new();
+    set.spawn(Duration::from_millis(100), async {
+        tokio::time::sleep(Duration::from_secs(999)).await;
+        1
+    });
+    set.spawn(Duration::from_secs(999), async { 2 });
+    let aborted = set.spawn(Duration::from_secs(999), async {
+        tokio::time::sleep(Duration::from_secs(999)).await;
+        3
+    });
+    aborted.abort();
+    assert_eq!(set.len(), 3);
+
+    let mut completed = vec![];
+    let mut timed_out = 0;
+    let mut cancelled = 0;
+    while let Some(res) = set.join_next().await {
+        match res {
+            Ok(Ok(value)) => completed.push(value),
+            Ok(Err(TimedOutError)) => timed_out += 1,
+            Err(err) => {
+                assert!(err.is_cancelled());
+                cancelled += 1;
+            }
+        }
+    }
+
+    assert_eq!(completed, vec![2]);
+    assert_eq!(timed_out, 1);
+    assert_eq!(cancelled, 1);
+    assert!(set.is_empty());
+}
+
+#[tokio::test]
+async fn hedge_table_test() {
+    // (first attempt duration, first attempt result, expected attempts, expected result)
+    type Outcome = Result<usize, usize>;
+    let delay = Duration::from_millis(200);
+    let cases: Vec<(Duration, Outcome, usize, Outcome)> = vec![
+        (Duration::from_millis(10), Ok(1), 1, Ok(1)), // fast first attempt, no hedge
+        (Duration::from_millis(10), Err(1), 1, Err(1)), // fast failure, no hedge
+        (Duration::from_secs(999), Ok(1), 2, Ok(2)),  // stuck first attempt is hedged
+        (Duration::from_millis(400), Err(1), 2, Ok(2)), // hedged attempt wins
+    ];
+
+    for (first_duration, first_result, expected_attempts, expected_result) in cases {
+        let attempts = AtomicUsize::new(0);
+        let res = crate::hedge(delay, || {
+            let attempt = attempts.fetch_add(1, AtomicOrdering::SeqCst) + 1;
+            async move {
+                if attempt == 1 {
+                    tokio::time::sleep(first_duration).await;
+                    first_result
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(res, expected_result);
+        assert_eq!(attempts.load(AtomicOrdering::SeqCst), expected_attempts);
+    }
+}
+
+// If both attempts fail, the error of the attempt that finished last is returned.
+#[tokio::test]
+async fn hedge_both_fail_test() {
+    let attempts = AtomicUsize::new(0);
+    let res: Result<(), usize> = crate::hedge(Duration::from_millis(100), || {
+        let attempt = attempts.fetch_add(1, AtomicOrdering::SeqCst) + 1;
+        async move {
+            if attempt == 1 {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            }
+            Err(attempt)
+        }
+    })
+    .await;
+
+    assert_eq!(res, Err(1));
+}
+
+// The named Sleep future must re-arm itself when reset, including after it has
+// already completed.
+#[tokio::test]
+async fn sleep_reset_test() {
+    let sleep = crate::sleep(Duration::from_millis(10));
+    tokio::pin!(sleep);
+    (&mut sleep).await;
+    assert!(sleep.is_elapsed());
+
+    let start = Instant::now();
+    let deadline = SuspendUnawareInstant::now() + Duration::from_millis(200);
+    sleep.as_mut().reset(deadline);
+    assert_eq!(sleep.deadline(), deadline);
+    assert!(!sleep.is_elapsed());
+    (&mut sleep).await;
+
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(SuspendUnawareInstant::now() >= deadline);
+}
+
+#[tokio::test]
+async fn timeout_stream_read_write_test() {
+    // the peer never reads or writes, and the pipe buffer is tiny
+    let (stream, _peer) = tokio::io::duplex(4);
+    let mut stream = TimeoutStream::new(stream);
+    stream.set_read_timeout(Some(Duration::from_millis(100)));
+    stream.set_write_timeout(Some(Duration::from_millis(100)));
+
+    let start = Instant::now();
+    let err = stream.read(&mut [0; 8]).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    let start = Instant::now();
+    let err = stream.write_all(&[0; 64]).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+// Timeouts the clock cannot represent never fire, rather than wrapping around
+// to an instant in the past.
+#[tokio::test]
+async fn timeout_stream_max_timeout_test() {
+    let (stream, _peer) = tokio::io::duplex(4);
+    let mut stream = TimeoutStream::new(stream);
+    stream.set_read_timeout(Some(Duration::MAX));
+    stream.set_idle_timeout(Some(Duration::MAX));
+
+    let res = crate::timeout(Duration::from_millis(200), stream.read(&mut [0; 8])).await;
+    assert_eq!(res.err(), Some(TimedOutError));
+}
+
+// An expired timeout only fails the operation it fired on: the next read gets a
+// fresh timeout, and completes when the peer finally writes.
+#[tokio::test]
+async fn timeout_stream_read_after_timeout_test() {
+    let (stream, mut peer) = tokio::io::duplex(64);
+    let mut stream = TimeoutStream::new(stream);
+    stream.set_read_timeout(Some(Duration::from_millis(100)));
+    stream.set_idle_timeout(Some(Duration::from_millis(100)));
+
+    let err = stream.read(&mut [0; 8]).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        peer.write_all(&[1]).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(999)).await;
+    });
+    assert_eq!(stream.read(&mut [0; 8]).await.unwrap(), 1);
+}
+
+// Like the baseline async fn, creating a sleep or a stream does not need a
+// runtime.
+#[test]
+fn sleep_outside_runtime_test() {
+    let sleep = crate::sleep(Duration::from_millis(10));
+    let (stream, _peer) = tokio::io::duplex(64);
+    let _stream = TimeoutStream::new(stream);
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .unwrap()
+        .block_on(sleep);
+}
+
+// Progress resets the timeouts, so a slow but steady peer never times out, while
+// a peer that goes quiet trips the idle timeout.
+#[tokio::test]
+async fn timeout_stream_idle_test() {
+    let (stream, mut peer) = tokio::io::duplex(64);
+    let mut stream = TimeoutStream::new(stream);
+    stream.set_read_timeout(Some(Duration::from_millis(200)));
+    stream.set_idle_timeout(Some(Duration::from_millis(200)));
+
+    tokio::spawn(async move {
+        for _ in 0..6 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            peer.write_all(&[1]).await.unwrap();
+        }
+        // keep the peer open without writing
+        tokio::time::sleep(Duration::from_secs(999)).await;
+    });
+
+    for _ in 0..6 {
+        assert_eq!(stream.read(&mut [0; 8]).await.unwrap(), 1);
+    }
+    let err = stream.read(&mut [0; 8]).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+}
+
+// A duration too long to be represented means "never", on every clock.
+#[tokio::test]
+async fn timeout_with_policy_overflow_test() {
+    let policies = [
+        TimeoutPolicy::ExcludeSuspend,
+        TimeoutPolicy::IncludeSuspend,
+        TimeoutPolicy::WallClock,
+    ];
+
+    for policy in policies {
+        let res = crate::timeout_with(
+            policy,
+            Duration::MAX,
+            crate::sleep(Duration::from_millis(10)),
+        )
+        .await;
+        assert_eq!(res, Ok(()), "{policy:?}");
+        let res = crate::timeout(
+            Duration::from_millis(10),
+            crate::sleep_with(policy, Duration::MAX),
+        )
+        .await;
+        assert_eq!(res, Err(TimedOutError), "{policy:?}");
+    }
+}
+
+// Without a suspend, every policy should sleep for the same amount of time.
+#[tokio::test]
+async fn sleep_with_policy_test() {
+    let policies = [
+        TimeoutPolicy::ExcludeSuspend,
+        TimeoutPolicy::IncludeSuspend,
+        TimeoutPolicy::WallClock,
+    ];
+    for policy in policies {
+        let start = Instant::now();
+        crate::sleep_with(policy, Duration::from_millis(200)).await;
+        let elapsed = start.elapsed();
+
+        assert!(elapsed >= Duration::from_millis(200));
+        assert!(elapsed.as_millis().abs_diff(200) < TOLERANCE_MS_U128);
+    }
+}
+
+#[tokio::test]
+async fn timeout_with_policy_test() {
+    let policies = [
+        TimeoutPolicy::ExcludeSuspend,
+        TimeoutPolicy::IncludeSuspend,
+        TimeoutPolicy::WallClock,
+    ];
+    for policy in policies {
+        let res = crate::timeout_with(
+            policy,
+            Duration::from_millis(100),
+            tokio::time::sleep(Duration::from_secs(999)),
+        )
+        .await;
+        assert_eq!(res, Err(TimedOutError));
+
+        let res = crate::timeout_with(policy, Duration::from_secs(999), async { 1 }).await;
+        assert_eq!(res, Ok(1));
+    }
+}
+
+// Without a suspend, the grace period never applies.
+#[tokio::test]
+async fn timeout_with_resume_grace_test() {
+    let start = Instant::now();
+    let res = crate::timeout_with_resume_grace(
+        Duration::from_millis(1500),
+        Duration::from_secs(999),
+        tokio::time::sleep(Duration::from_secs(999)),
+    )
+    .await;
+    assert_eq!(res, Err(TimedOutError));
+    assert!(start.elapsed().as_millis().abs_diff(1500) < TOLERANCE_MS_U128);
+
+    let res = crate::timeout_with_resume_grace(
+        Duration::from_secs(2),
+        Duration::from_secs(999),
+        tokio::time::sleep(Duration::from_millis(100)),
+    )
+    .await;
+    assert!(res.is_ok());
+
+    // durations the clock cannot represent never time out
+    let res = crate::timeout_with_resume_grace(
+        Duration::MAX,
+        Duration::MAX,
+        tokio::time::sleep(Duration::from_millis(300)),
+    )
+    .await;
+    assert!(res.is_ok());
+}
+
+#[test]
+fn resume_detector_test() {
+    let mut detector = crate::resume::ResumeDetector::new();
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(detector.check(), None);
+}
+
+// Without a suspend, the future runs to completion, even across several rechecks.
+#[tokio::test]
+async fn cancel_on_resume_test() {
+    let res = crate::cancel_on_resume(async {
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        1
+    })
+    .await;
+    assert_eq!(res, Ok(1));
+}
+
+#[tokio::test]
+async fn sleep_with_report_test() {
+    let duration = Duration::from_millis(200);
+    let report = crate::sleep_with_report(duration).await;
+
+    assert!(report.active >= duration);
+    assert_eq!(report.lateness, report.active - duration);
+    assert!(report.lateness < Duration::from_millis(TOLERANCE_MS));
+    // nothing suspends the system during tests
+    assert!(report.suspended < Duration::from_millis(TOLERANCE_MS));
+
+    // like sleep, a duration the clock cannot represent never completes
+    let res = crate::timeout(
+        Duration::from_millis(100),
+        crate::sleep_with_report(Duration::MAX),
+    )
+    .await;
+    assert_eq!(res, Err(TimedOutError));
+}
+
+#[tokio::test]
+async fn sleep_until_wall_test() {
+    // a deadline in the past completes immediately
+    let start = Instant::now();
+    crate::sleep_until_wall(SystemTime::now() - Duration::from_secs(60)).await;
+    assert!(start.elapsed() < Duration::from_millis(TOLERANCE_MS));
+
+    // a deadline further away than the recheck interval is still hit on time
+    let deadline = SystemTime::now() + Duration::from_millis(1500);
+    crate::sleep_until_wall(deadline).await;
+    let now = SystemTime::now();
+    assert!(now >= deadline);
+    assert!(now.duration_since(deadline).unwrap() < Duration::from_millis(TOLERANCE_MS));
+}
+
+#[test]
+fn cron_next_after_tests() {
+    let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    // (expression, time, expected next run)
+    #[rustfmt::skip]
+    let cases = [
+        ("30 9 * * 1-5", at(1720224000), Some(at(1720431000))), // saturday 2024-07-06 --> monday 09:30
+        ("* * * * *", at(1709164770), Some(at(1709164800))), // next minute, across a leap day
+        ("0 0 29 2 *", at(1709164800), Some(at(1835395200))), // strictly after, so the next leap day
+        ("0 0 1 1 *", at(1735689540), Some(at(1735689600))), // new year
+        ("0 12 * * 0", at(1720224000), Some(at(1720353600))), // sunday
+        ("0 12 * * 7", at(1720224000), Some(at(1720353600))), // 7 is also sunday
+        ("*/15 * * * *", at(1720224000), Some(at(1720224900))), // step
+        ("0 0 30 2 *", at(1720224000), None), // never matches
+    ];
+
+    for (expression, time, expected_result) in cases {
+        let schedule: CronSchedule = expression.parse().unwrap();
+        assert_eq!(schedule.next_after(time), expected_result, "{expression}");
+    }
+}
+
+#[test]
+fn cron_parse_error_tests() {
+    let cases = [
+        "* * * *",     // too few fields
+        "* * * * * *", // too many fields
+        "60 * * * *",  // out of range
+        "* * 0 * *",   // out of range
+        "5-1 * * * *", // reversed range
+        "*/0 * * * *", // zero step
+        "a * * * *",   // not a number
+    ];
+
+    for expression in cases {
+        assert!(CronSchedule::parse(expression).is_err(), "{expression}");
+    }
+}
+
+#[tokio::test]
+async fn scheduler_every_test() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut scheduler = Scheduler::new();
+    let handle = {
+        let runs = runs.clone();
+        scheduler.add(
+            Schedule::Every(Duration::from_millis(200)),
+            MissedRunPolicy::RunOnce,
+            move || {
+                runs.fetch_add(1, AtomicOrdering::SeqCst);
+                async {}
+            },
+        )
+    };
+    assert!(matches!(handle.next_run(), Some(NextRun::Active(_))));
+
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    let before_pause = runs.load(AtomicOrdering::SeqCst);
+    assert!((2..=4).contains(&before_pause));
+
+    handle.pause();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(handle.is_paused());
+    assert_eq!(handle.next_run(), None);
+    let paused = runs.load(AtomicOrdering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(runs.load(AtomicOrdering::SeqCst), paused);
+
+    handle.resume();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(runs.load(AtomicOrdering::SeqCst) > paused);
+
+    handle.cancel();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let cancelled = runs.load(AtomicOrdering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(handle.is_cancelled());
+    assert_eq!(runs.load(AtomicOrdering::SeqCst), cancelled);
+    assert_eq!(handle.next_run(), None);
+}
+
+// A job which is always due must still let the (single threaded) runtime make
+// progress.
+#[tokio::test]
+async fn scheduler_zero_period_test() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut scheduler = Scheduler::new();
+    let handle = {
+        let runs = runs.clone();
+        scheduler.add(
+            Schedule::Every(Duration::ZERO),
+            MissedRunPolicy::RunOnce,
+            move || {
+                runs.fetch_add(1, AtomicOrdering::SeqCst);
+                async {}
+            },
+        )
+    };
+
+    // a period the clock cannot represent never comes, rather than wrapping
+    // around to an instant in the past
+    let never_runs = Arc::new(AtomicUsize::new(0));
+    let never_handle = {
+        let never_runs = never_runs.clone();
+        scheduler.add(
+            Schedule::Every(Duration::MAX),
+            MissedRunPolicy::RunOnce,
+            move || {
+                never_runs.fetch_add(1, AtomicOrdering::SeqCst);
+                async {}
+            },
+        )
+    };
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(runs.load(AtomicOrdering::SeqCst) > 0);
+    assert_eq!(never_runs.load(AtomicOrdering::SeqCst), 0);
+    handle.cancel();
+    never_handle.cancel();
+    scheduler.shutdown().await;
+}
+
+#[tokio::test]
+async fn scheduler_missed_run_test() {
+    let now = SystemTime::now();
+    // (run time, policy, expected runs)
+    let cases = [
+        (now + Duration::from_millis(200), MissedRunPolicy::Skip, 1), // on time
+        (now - Duration::from_secs(60), MissedRunPolicy::RunOnce, 1), // missed
+        (now - Duration::from_secs(60), MissedRunPolicy::RunAll, 1),  // missed, only one run due
+        (now - Duration::from_secs(60), MissedRunPolicy::Skip, 0),    // missed
+    ];
+
+    let mut scheduler = Scheduler::new();
+    let mut jobs = vec![];
+    for (time, policy, expected_runs) in cases {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let handle = {
+            let runs = runs.clone();
+            scheduler.add(Schedule::At(time), policy, move || {
+                runs.fetch_add(1, AtomicOrdering::SeqCst);
+                async {}
+            })
+        };
+        assert_eq!(handle.next_run(), Some(NextRun::Wall(time)));
+        jobs.push((handle, runs, expected_runs));
+    }
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    for (handle, runs, expected_runs) in jobs {
+        assert_eq!(runs.load(AtomicOrdering::SeqCst), expected_runs);
+        assert_eq!(handle.next_run(), None);
+    }
+}
+
+#[test]
+fn cached_clock_test() {
+    let interval = Duration::from_millis(10);
+    let clock = CachedClock::new(interval);
+    assert_eq!(clock.interval(), interval);
+
+    let first = clock.recent();
+    std::thread::sleep(Duration::from_millis(100));
+    let second = clock.recent();
+    let now = SuspendUnawareInstant::now();
+
+    // the clock is refreshed in the background, and is never ahead of now()
+    assert!(second > first);
+    assert!(second <= now);
+    assert!(now - second < interval + Duration::from_millis(TOLERANCE_MS));
+}
+
+// A zero interval would make the background thread spin, so it is raised.
+#[test]
+fn cached_clock_min_interval_test() {
+    let clock = CachedClock::new(Duration::ZERO);
+    assert_eq!(clock.interval(), Duration::from_millis(1));
+    assert!(clock.recent() <= SuspendUnawareInstant::now());
+}
+
+#[test]
+fn now_coarse_test() {
+    let resolution = SuspendUnawareInstant::coarse_resolution();
+    assert!(resolution > Duration::ZERO);
+    assert!(resolution >= SuspendUnawareInstant::resolution());
+
+    // the coarse clock shares the precise clock's epoch, it only lags behind
+    let precise = SuspendUnawareInstant::now();
+    let coarse = SuspendUnawareInstant::now_coarse();
+    assert!((precise - coarse).as_millis() < TOLERANCE_MS_U128);
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(coarse.elapsed() >= Duration::from_millis(100));
+    assert!(SuspendUnawareInstant::now_coarse() > coarse);
+}
+
+#[test]
+fn clock_info_test() {
+    let info = crate::clock_info();
+    assert!(!info.backend.is_empty());
+    assert!(!info.clock.is_empty());
+    assert_eq!(info.resolution, SuspendUnawareInstant::resolution());
+    assert!(info.suspend_excluding);
+}
+
+// The process-wide guard cannot be turned off again and would leak into every
+// other test, so these tests use a guard of their own.
+#[test]
+fn monotonicity_guard_test() {
+    let guard = crate::monotonic::Guard::new();
+    let last_read = std::cell::Cell::new(0);
+
+    let mut last = guard.apply(SuspendUnawareInstant::now(), 0, &last_read);
+    for _ in 0..10_000 {
+        let now = guard.apply(SuspendUnawareInstant::now(), 0, &last_read);
+        assert!(now >= last);
+        last = now;
+    }
+}
+
+#[test]
+fn monotonicity_guard_anomalies_test() {
+    let guard = crate::monotonic::Guard::new();
+    let last_read = std::cell::Cell::new(0);
+    guard.set_forward_jump_threshold(Duration::from_secs(60));
+
+    // (reading, wall clock secs, expected result, expected backwards steps, expected forward jumps)
+    #[rustfmt::skip]
+    let cases = [
+        (create_instant(100, 0), 1_000, create_instant(100, 0), 0, 0),
+        (create_instant(100, 5), 1_000, create_instant(100, 5), 0, 0),
+        (create_instant(99, 0), 1_000, create_instant(100, 5), 1, 0), // backwards step, clamped
+        (create_instant(150, 0), 1_000, create_instant(150, 0), 1, 0), // within the threshold
+        (create_instant(500, 0), 1_000, create_instant(500, 0), 1, 1), // forward jump
+        (create_instant(10_000, 0), 10_500, create_instant(10_000, 0), 1, 1), // idle gap
+    ];
+
+    for (reading, wall_secs, expected_result, backwards_steps, forward_jumps) in cases {
+        let wall = wall_secs * NANOS_PER_SECOND as u64;
+        assert_eq!(guard.apply(reading, wall, &last_read), expected_result);
+        assert_eq!(
+            guard.anomalies(),
+            ClockAnomalies {
+                backwards_steps,
+                forward_jumps
+            }
+        );
+    }
+}
+
+#[test]
+fn nanos_since_boot_tests() {
+    // (instant, nanoseconds since boot)
+    #[rustfmt::skip]
+    let cases = [
+        (create_instant(0, 0), 0),
+        (create_instant(0, 999_999_999), 999_999_999),
+        (create_instant(3, 5), 3_000_000_005),
+        (create_instant(u64::MAX, 999_999_999), u64::MAX as u128 * 1_000_000_000 + 999_999_999),
+    ];
+
+    for (instant, nanos) in cases {
+        assert_eq!(instant.as_nanos_since_boot(), nanos);
+        assert_eq!(
+            SuspendUnawareInstant::from_nanos_since_boot(nanos),
+            Some(instant)
+        );
+        assert_eq!(
+            SuspendUnawareInstant::from_duration_since_boot(instant.as_duration_since_boot()),
+            instant
+        );
+    }
+
+    assert_eq!(
+        SuspendUnawareInstant::from_nanos_since_boot(u128::MAX),
+        None
+    );
+
+    let now = SuspendUnawareInstant::now();
+    let round_trip = SuspendUnawareInstant::from_nanos_since_boot(now.as_nanos_since_boot());
+    assert_eq!(round_trip, Some(now));
+}
+
+#[test]
+fn boot_id_test() {
+    let boot_id = crate::boot_id().unwrap();
+    assert!(!boot_id.is_empty());
+    assert_eq!(crate::boot_id().unwrap(), boot_id);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Expiring {
+        #[serde(with = "crate::serde::expire_on_reboot")]
+        deadline: SuspendUnawareInstant,
+    }
+
+    let instant = create_instant(42, 5);
+    let json = serde_json::to_string(&instant).unwrap();
+    assert_eq!(
+        serde_json::from_str::<SuspendUnawareInstant>(&json).unwrap(),
+        instant
+    );
+
+    let other_boot = serde_json::json!({"boot_id": "previous boot", "secs": 42, "nanos": 5});
+    let err = serde_json::from_value::<SuspendUnawareInstant>(other_boot.clone()).unwrap_err();
+    assert!(err.to_string().contains("another boot"), "{err}");
+
+    let expired: Expiring =
+        serde_json::from_value(serde_json::json!({ "deadline": other_boot })).unwrap();
+    assert_eq!(expired.deadline, create_instant(0, 0));
+    let current: Expiring = serde_json::from_str(&format!("{{\"deadline\":{json}}}")).unwrap();
+    assert_eq!(current.deadline, instant);
+
+    let invalid = serde_json::json!({"boot_id": crate::boot_id().unwrap(), "secs": 0, "nanos": 1_000_000_000});
+    assert!(serde_json::from_value::<SuspendUnawareInstant>(invalid).is_err());
+}
+
+#[test]
+fn clock_snapshot_test() {
+    let event = SuspendUnawareInstant::now();
+    let event_wall = SystemTime::now();
+    std::thread::sleep(Duration::from_millis(100));
+
+    let snapshot = crate::ClockSnapshot::now();
+    assert!(snapshot.uncertainty().as_millis() < TOLERANCE_MS_U128);
+    assert_eq!(
+        snapshot.wall_time_of(snapshot.suspend_unaware()),
+        Some(snapshot.wall())
+    );
+    assert_eq!(
+        snapshot.wall_time_of(snapshot.suspend_unaware() + Duration::from_secs(1)),
+        Some(snapshot.wall() + Duration::from_secs(1))
+    );
+
+    let estimate = snapshot.wall_time_of(event).unwrap();
+    let error = match estimate.duration_since(event_wall) {
+        Ok(error) => error,
+        Err(e) => e.duration(),
+    };
+    assert!(error.as_millis() < TOLERANCE_MS_U128);
+    assert!(snapshot.earliest_wall_time_of(event).unwrap() <= estimate);
+
+    // too far in the future to be represented
+    let far = snapshot.suspend_unaware() + Duration::from_secs(u64::MAX / 2);
+    assert_eq!(snapshot.wall_time_of(far), None);
+
+    let later = crate::ClockSnapshot::now();
+    assert!(later.suspended_since(&snapshot) <= later.suspended_since_boot());
+}
+
+#[tokio::test]
+async fn instant_conversion_test() {
+    let tolerance = Duration::from_millis(TOLERANCE_MS);
+    let now = SuspendUnawareInstant::now();
+
+    // (offset from now, in the future)
+    #[rustfmt::skip]
+    let cases = [
+        (Duration::ZERO, true),
+        (Duration::from_secs(5), true),
+        (Duration::from_secs(5), false),
+    ];
+
+    for (offset, future) in cases {
+        let (instant, std_expected, tokio_expected) = if future {
+            (
+                now + offset,
+                std::time::Instant::now() + offset,
+                tokio::time::Instant::now() + offset,
+            )
+        } else {
+            (
+                now - offset,
+                std::time::Instant::now() - offset,
+                tokio::time::Instant::now() - offset,
+            )
+        };
+
+        let std_instant = instant.to_std_instant();
+        assert!(std_instant.max(std_expected) - std_instant.min(std_expected) < tolerance);
+        let tokio_instant = instant.to_tokio_instant();
+        assert!(tokio_instant.max(tokio_expected) - tokio_instant.min(tokio_expected) < tolerance);
+
+        for round_trip in [
+            SuspendUnawareInstant::from_std_instant(std_instant),
+            SuspendUnawareInstant::from_tokio_instant(tokio_instant),
+        ] {
+            assert!(round_trip.max(instant) - round_trip.min(instant) < tolerance);
+        }
+    }
+}
+
+// Far-off deadlines are clamped rather than overflowing the std and tokio
+// instants.
+#[tokio::test]
+async fn instant_conversion_overflow_test() {
+    let far = Duration::from_secs(u64::MAX / 2);
+    let deadline = SuspendUnawareInstant::now() + far;
+
+    assert!(deadline.to_std_instant() > std::time::Instant::now() + Duration::from_secs(86400));
+    assert!(deadline.to_tokio_instant() > tokio::time::Instant::now() + Duration::from_secs(86400));
+
+    let far_std = std::time::Instant::now() + Duration::from_secs(86400 * 365);
+    assert!(SuspendUnawareInstant::from_std_instant(far_std) > SuspendUnawareInstant::now());
+}
+
+#[test]
+fn signed_duration_since_tests() {
+    // (instant, earlier, expected nanoseconds)
+    #[rustfmt::skip]
+    let cases = [
+        (create_instant(0, 0), create_instant(0, 0), 0),
+        (create_instant(2, 0), create_instant(1, 999_999_999), 1),
+        (create_instant(1, 999_999_999), create_instant(2, 0), -1),
+        (create_instant(5, 0), create_instant(0, 500), 4_999_999_500),
+        (create_instant(0, 500), create_instant(5, 0), -4_999_999_500),
+        (create_instant(u64::MAX, 999_999_999), create_instant(0, 0), u64::MAX as i128 * 1_000_000_000 + 999_999_999),
+        (create_instant(0, 0), create_instant(u64::MAX, 999_999_999), -(u64::MAX as i128 * 1_000_000_000 + 999_999_999)),
+    ];
+
+    for (instant, earlier, expected) in cases {
+        let signed = instant.signed_duration_since(earlier);
+        assert_eq!(signed.as_nanos(), expected);
+        assert_eq!(signed.is_negative(), expected < 0);
+        assert_eq!(signed.signum(), expected.cmp(&0));
+        assert_eq!(signed.abs().as_nanos(), expected.unsigned_abs());
+        assert_eq!(-signed, earlier.signed_duration_since(instant));
+    }
+
+    assert_eq!(-SignedDuration::ZERO, SignedDuration::ZERO);
+    assert!(
+        SignedDuration::negative(Duration::from_secs(2))
+            < SignedDuration::negative(Duration::from_secs(1))
+    );
+    assert!(
+        SignedDuration::negative(Duration::from_secs(1))
+            < SignedDuration::from(Duration::from_nanos(1))
+    );
+}
+
+#[test]
+fn compact_instant_conversion_tests() {
+    // (instant, nanoseconds, or None if it does not fit)
+    #[rustfmt::skip]
+    let cases = [
+        (create_instant(0, 0), Some(0)),
+        (create_instant(1, 1), Some(1_000_000_001)),
+        (create_instant(18_446_744_073, 709_551_615), Some(u64::MAX)),
+        (create_instant(18_446_744_073, 709_551_616), None),
+        (create_instant(u64::MAX, 0), None),
+    ];
+
+    for (instant, nanos) in cases {
+        let compact = CompactInstant::try_from(instant);
+        assert_eq!(
+            compact,
+            nanos
+                .map(CompactInstant::from_nanos_since_boot)
+                .ok_or(CompactInstantOverflowError)
+        );
+        if let Ok(compact) = compact {
+            assert_eq!(SuspendUnawareInstant::from(compact), instant);
+        }
+    }
+
+    let now = CompactInstant::now();
+    assert!(SuspendUnawareInstant::from(now) <= SuspendUnawareInstant::now());
+}
+
+#[test]
+fn compact_instant_arithmetic_tests() {
+    let compact = CompactInstant::from_nanos_since_boot;
+
+    // (instant, duration, instant + duration, instant - duration)
+    #[rustfmt::skip]
+    let cases = [
+        (compact(0), Duration::ZERO, compact(0), compact(0)),
+        (compact(5), Duration::from_nanos(3), compact(8), compact(2)),
+        (compact(5), Duration::from_nanos(6), compact(11), compact(0)),
+        (compact(u64::MAX), Duration::from_nanos(1), compact(0), compact(u64::MAX - 1)),
+        (compact(1), Duration::MAX, compact(0), compact(0)),
+    ];
+
+    for (instant, duration, added, subtracted) in cases {
+        assert_eq!(instant + duration, added);
+        assert_eq!(instant - duration, subtracted);
+
+        // the same as SuspendUnawareInstant, wherever both fit
+        let wide = SuspendUnawareInstant::from(instant);
+        if let Ok(wide_added) = CompactInstant::try_from(wide + duration) {
+            assert_eq!(wide_added, added);
+        }
+        assert_eq!(CompactInstant::try_from(wide - duration), Ok(subtracted));
+    }
+
+    assert_eq!(compact(10) - compact(4), Duration::from_nanos(6));
+    assert_eq!(compact(4) - compact(10), Duration::ZERO);
+}
+
+#[test]
+fn atomic_compact_instant_test() {
+    let compact = CompactInstant::from_nanos_since_boot;
+    let atomic = AtomicCompactInstant::new(compact(10));
+
+    assert_eq!(
+        atomic.fetch_max(compact(5), AtomicOrdering::Relaxed),
+        compact(10)
+    );
+    assert_eq!(atomic.load(AtomicOrdering::Relaxed), compact(10));
+    assert_eq!(
+        atomic.fetch_max(compact(20), AtomicOrdering::Relaxed),
+        compact(10)
+    );
+    assert_eq!(atomic.load(AtomicOrdering::Relaxed), compact(20));
+
+    assert_eq!(
+        atomic.compare_exchange(
+            compact(10),
+            compact(30),
+            AtomicOrdering::Relaxed,
+            AtomicOrdering::Relaxed
+        ),
+        Err(compact(20))
+    );
+    assert_eq!(
+        atomic.compare_exchange(
+            compact(20),
+            compact(30),
+            AtomicOrdering::Relaxed,
+            AtomicOrdering::Relaxed
+        ),
+        Ok(compact(20))
+    );
+    assert_eq!(
+        atomic.swap(compact(1), AtomicOrdering::Relaxed),
+        compact(30)
+    );
+    atomic.store(compact(2), AtomicOrdering::Relaxed);
+    assert_eq!(atomic.into_inner(), compact(2));
+
+    // concurrent updates keep the latest instant
+    let atomic = Arc::new(AtomicCompactInstant::default());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let atomic = atomic.clone();
+            std::thread::spawn(move || {
+                let mut latest = CompactInstant::default();
+                for _ in 0..1000 {
+                    latest = CompactInstant::now();
+                    atomic.fetch_max(latest, AtomicOrdering::Relaxed);
+                }
+                latest
+            })
+        })
+        .collect();
+    let latest = handles
+        .into_iter()
+        .map(|h| h.join().unwrap())
+        .max()
+        .unwrap();
+    assert_eq!(atomic.load(AtomicOrdering::Relaxed), latest);
+}
+
+#[test]
+fn cpu_instant_test() {
+    let active = SuspendUnawareInstant::now();
+    let thread_cpu = ThreadCpuInstant::now();
+    let process_cpu = ProcessCpuInstant::now();
+
+    // sleeping does not use the CPU
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(thread_cpu.elapsed() < Duration::from_millis(100));
+
+    // spinning does, and never faster than real time
+    while thread_cpu.elapsed() < Duration::from_millis(100) {}
+    let thread_elapsed = thread_cpu.elapsed();
+    assert!(thread_elapsed <= active.elapsed());
+    assert!(process_cpu.elapsed() >= thread_elapsed);
+
+    assert_eq!(
+        (thread_cpu + Duration::from_secs(1)) - thread_cpu,
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        thread_cpu - (thread_cpu + Duration::from_secs(1)),
+        Duration::ZERO
+    );
+    assert!(thread_cpu
+        .signed_duration_since(ThreadCpuInstant::now())
+        .is_negative());
+}
+
+#[test]
+fn generic_instant_tests() {
+    fn check<C: ClockSource>() {
+        let now = crate::Instant::<C>::now();
+        let later = now + Duration::from_secs(1);
+
+        assert!(later > now);
+        assert_eq!(later - now, Duration::from_secs(1));
+        assert_eq!(now - later, Duration::ZERO);
+        assert_eq!(later - Duration::from_secs(1), now);
+        assert!(now.signed_duration_since(later).is_negative());
+        assert!(crate::Instant::<C>::now() >= now);
+    }
+
+    check::<SuspendUnaware>();
+    check::<SuspendAware>();
+    check::<Raw>();
+    check::<ThreadCpuTime>();
+    check::<ProcessCpuTime>();
+}
+
+// Debug output names the clock-specific type, as it did before the instants
+// became generic.
+#[test]
+fn instant_debug_test() {
+    assert_eq!(
+        format!("{:?}", create_instant(1, 2)),
+        "SuspendUnawareInstant { secs: 1, nanos: 2 }"
+    );
+    assert!(format!("{:?}", SuspendAwareInstant::now()).starts_with("SuspendAwareInstant {"));
+    assert!(
+        format!("{:?}", RawSuspendUnawareInstant::now()).starts_with("RawSuspendUnawareInstant {")
+    );
+    assert!(format!("{:?}", ThreadCpuInstant::now()).starts_with("ThreadCpuInstant {"));
+    assert!(format!("{:?}", ProcessCpuInstant::now()).starts_with("ProcessCpuInstant {"));
+}
+
+#[test]
+fn raw_instant_test() {
+    let raw = RawSuspendUnawareInstant::now();
+    let slewed = SuspendUnawareInstant::now();
+    std::thread::sleep(Duration::from_millis(100));
+    let (raw, slewed) = (raw.elapsed(), slewed.elapsed());
+
+    // NTP slews by at most 0.05%, far below the scheduling noise
+    let diff = raw.max(slewed) - raw.min(slewed);
+    assert!(
+        diff.as_millis() < TOLERANCE_MS_U128,
+        "{raw:?} vs {slewed:?}"
+    );
+}
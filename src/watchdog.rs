@@ -0,0 +1,125 @@
+use crate::{sleep, SuspendUnawareInstant};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// An inactivity timer that must be periodically fed, measured with
+/// [`SuspendUnawareInstant`].
+///
+/// If [`Watchdog::feed`] is not called within `period`, the watchdog is
+/// considered starved: [`Watchdog::expired`] resolves and any callback
+/// registered with [`Watchdog::on_expire`] runs. Since time does not pass while
+/// the system is suspended, a suspend never counts as a missed heartbeat.
+///
+/// Cloning a `Watchdog` is cheap, and all clones share the same timer, so a
+/// clone can be handed to each thread or task that feeds it.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use suspend_time::Watchdog;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let watchdog = Watchdog::new(Duration::from_millis(100));
+///     let worker = watchdog.clone();
+///     tokio::spawn(async move {
+///         // a worker loop that hangs after its first iteration
+///         worker.feed();
+///     });
+///
+///     watchdog.expired().await;
+///     assert!(watchdog.is_expired());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Watchdog {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    period: Duration,
+    created: SuspendUnawareInstant,
+    // nanoseconds between `created` and the last feed. Storing an offset rather
+    // than the instant itself lets `feed()` be a single atomic operation.
+    last_feed: AtomicU64,
+}
+
+impl Watchdog {
+    /// Creates a watchdog which starves if it is not fed for `period`. The
+    /// watchdog counts as fed at creation.
+    pub fn new(period: Duration) -> Watchdog {
+        Watchdog {
+            inner: Arc::new(Inner {
+                period,
+                created: SuspendUnawareInstant::now(),
+                last_feed: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Resets the inactivity timer. This is a single atomic operation, and is
+    /// safe to call from any number of threads.
+    pub fn feed(&self) {
+        let offset = self.inner.created.elapsed().as_nanos() as u64;
+        // fetch_max rather than store, so a thread that read the clock just
+        // before another one cannot move the last feed backwards.
+        self.inner.last_feed.fetch_max(offset, Ordering::Relaxed);
+    }
+
+    /// Returns the period the watchdog was created with.
+    pub fn period(&self) -> Duration {
+        self.inner.period
+    }
+
+    /// Returns the instant at which the watchdog was last fed.
+    pub fn last_fed(&self) -> SuspendUnawareInstant {
+        self.inner.created + Duration::from_nanos(self.inner.last_feed.load(Ordering::Relaxed))
+    }
+
+    /// Returns the instant at which the watchdog starves unless it is fed
+    /// again. Periods too long for the clock to represent saturate to the
+    /// largest instant, so such a watchdog never starves.
+    pub fn deadline(&self) -> SuspendUnawareInstant {
+        self.last_fed().saturating_add(self.inner.period)
+    }
+
+    /// Returns true if the watchdog was not fed within its period.
+    pub fn is_expired(&self) -> bool {
+        SuspendUnawareInstant::now() >= self.deadline()
+    }
+
+    /// Resolves once the watchdog was not fed within its period. If it is
+    /// already starved, this resolves immediately.
+    pub async fn expired(&self) {
+        loop {
+            let deadline = self.deadline();
+            let now = SuspendUnawareInstant::now();
+            if now >= deadline {
+                return;
+            }
+            // The watchdog may be fed while we sleep, so the deadline is
+            // re-read every time we wake up.
+            sleep(deadline - now).await;
+        }
+    }
+
+    /// Spawns a tokio task which runs `callback` once the watchdog starves.
+    /// Aborting the returned handle disarms the callback.
+    pub fn on_expire<F>(&self, callback: F) -> tokio::task::JoinHandle<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let watchdog = self.clone();
+        tokio::spawn(async move {
+            watchdog.expired().await;
+            callback();
+        })
+    }
+}